@@ -3,4 +3,6 @@ pub mod misc {
     pub mod grid;
     pub mod graph;
     pub mod measure;
+    pub mod interner;
+    pub mod math;
 }
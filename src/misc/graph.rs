@@ -2,9 +2,19 @@ use std::{cmp::Ordering, collections::{BinaryHeap, HashMap}, hash::Hash, ops::Ad
 
 use itertools::Itertools;
 
+use super::{grid::Grid, point::Point};
+
+pub mod algorithms;
+pub mod cliques;
+pub mod export;
+pub mod labelled;
+pub mod paths;
+pub mod search;
+
 #[derive(Debug)]
 pub enum GraphError {
-    NoPath
+    NoPath,
+    UnknownVertex
 } 
 
 pub struct Graph<V,C> {
@@ -23,6 +33,86 @@ impl<V> Edge<V> {
     pub fn new(from: V, to: V) ->  Self { Edge { from, to} }
 }
 
+impl<V:Eq+Hash+Clone, C> Default for Graph<V,C> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<V:Eq+Hash+Clone, C> Graph<V,C> {
+    pub fn new() -> Self {
+        Graph { vertices: Vec::new(), edges: HashMap::new(), costs: HashMap::new() }
+    }
+
+    // Every known vertex has an entry in `edges`, so it doubles as the membership index.
+    pub fn contains(&self, v: &V) -> bool {
+        self.edges.contains_key(v)
+    }
+
+    pub fn add_vertex(&mut self, v: V) -> bool {
+        if self.contains(&v) { return false; }
+        self.edges.insert(v.clone(), Vec::new());
+        self.vertices.push(v);
+        true
+    }
+
+    pub fn add_edge(&mut self, from: V, to: V, cost: C) -> Result<(), GraphError> {
+        if !self.contains(&to) { return Err(GraphError::UnknownVertex); }
+        let edge = Edge::new(from, to);
+        let out = self.edges.get_mut(&edge.from).ok_or(GraphError::UnknownVertex)?;
+        if !out.contains(&edge) { out.push(edge.clone()); }
+        self.costs.insert(edge, cost);
+        Ok(())
+    }
+
+    pub fn add_undirected_edge(&mut self, a: V, b: V, cost: C) -> Result<(), GraphError> 
+    where C: Clone
+    {
+        self.add_edge(a.clone(), b.clone(), cost.clone())?;
+        self.add_edge(b, a, cost)
+    }
+
+    pub fn neighbors(&self, v: &V) -> impl Iterator<Item = (&V, &C)> {
+        self.edges.get(v).into_iter().flatten().map(|e| (&e.to, &self.costs[e]))
+    }
+
+    pub fn cost(&self, from: &V, to: &V) -> Option<&C> {
+        self.costs.get(&Edge::new(from.clone(), to.clone()))
+    }
+
+    pub fn from_edges(vertices: impl IntoIterator<Item = V>, edges: impl IntoIterator<Item = (V,V,C)>) -> Result<Self, GraphError> {
+        let mut g = Self::new();
+        vertices.into_iter().for_each(|v| { g.add_vertex(v); });
+        for (from, to, cost) in edges {
+            g.add_edge(from, to, cost)?;
+        }
+        Ok(g)
+    }
+
+    pub fn from_fn<I>(vertices: impl IntoIterator<Item = V>, adjacent: impl Fn(&V) -> I) -> Result<Self, GraphError> 
+    where I: IntoIterator<Item = (V,C)>
+    {
+        let mut g = Self::new();
+        vertices.into_iter().for_each(|v| { g.add_vertex(v); });
+        for v in g.vertices.clone() {
+            for (to, cost) in adjacent(&v) {
+                g.add_edge(v.clone(), to, cost)?;
+            }
+        }
+        Ok(g)
+    }
+}
+
+impl<C:Clone> Graph<Point,C> {
+    pub fn from_grid<T>(grid: &Grid<T>, passable: impl Fn(&T) -> bool, cost: C) -> Self {
+        let open = |p: &Point| grid.try_get(p).is_some_and(&passable);
+        Self::from_fn(grid.points_iter().filter(open), |p| p.ortho_neighbors()
+                .into_iter()
+                .filter(open)
+                .map(|n| (n, cost.clone()))
+                .collect_vec())
+            .expect("grid neighbours are always vertices")
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 struct DijkstraState<V,C> {
     position:V,
@@ -48,7 +138,7 @@ impl<V:Eq+Ord+Copy+Hash, C:Default+Copy+Ord+Add<Output = C>> Graph<V,C> {
             if distances[&position].as_ref().is_some_and(|d| cost > d.0) { continue; }
             if let Some(edges) = self.edges.get(&position) {
                 for edge in edges {
-                    let c = cost + self.costs[&edge];
+                    let c = cost + self.costs[edge];
                     let entry = distances.entry(&edge.to).or_default();
                     if entry.as_ref().is_none_or(|d| d.0 > c) {
                         *entry = Some((c, Vec::from([position])));
@@ -62,17 +152,80 @@ impl<V:Eq+Ord+Copy+Hash, C:Default+Copy+Ord+Add<Output = C>> Graph<V,C> {
         distances
     } 
 
+    fn successors(&self) -> impl Fn(&V) -> Vec<(V,C)> + '_ {
+        |v| self.neighbors(v).map(|(n, c)| (*n, *c)).collect_vec()
+    }
+
+    fn path_result(res: &search::SearchResult<V,C>, end: &V) -> Result<(C, Vec<V>), GraphError> {
+        let cost = res.distance(end).ok_or(GraphError::NoPath)?;
+        res.path_to(end).map(|p| (cost, p)).ok_or(GraphError::NoPath)
+    }
+
+    // `heuristic` has to be consistent, a lower bound on the remaining cost to `end`.
+    pub fn astar(&self, start:V, end:V, heuristic: impl Fn(&V) -> C) -> Result<(C, Vec<V>), GraphError> {
+        let res = search::astar(start, self.successors(), heuristic, |v| *v == end);
+        Self::path_result(&res, &end)
+    }
+
+    pub fn dijkstra_to_any(&self, start:V, targets:&[V]) -> Result<(V, C, Vec<V>), GraphError> {
+        let res = search::dijkstra(start, self.successors(), |v| targets.contains(v));
+        let end = *res.goals.first().ok_or(GraphError::NoPath)?;
+        Self::path_result(&res, &end).map(|(c, p)| (end, c, p))
+    }
+
+    pub fn dijkstra_to_all(&self, start:V, targets:&[V]) -> HashMap<V, (C, Vec<V>)> {
+        let res = search::dijkstra_all(start, self.successors(), targets);
+        res.goals.iter()
+            .filter_map(|t| Self::path_result(&res, t).ok().map(|p| (*t, p)))
+            .collect()
+    }
+
+    pub fn shortest_path_dag(&self, start:V) -> paths::PathDag<V> {
+        paths::PathDag::from_dijkstra(start, &self.dijkstra(start))
+    }
+
     pub fn find_path(&self, start:V, end:V) -> Result<Vec<V>, GraphError> {
-        let visits = self.dijkstra(start);
-        let mut path = Vec::new();
-        let mut v = &end;
-        while v != &start {
-            let visit = visits.get(v).ok_or(GraphError::NoPath)?;
-            path.push(v.clone());
-            v = visit.as_ref().and_then(|x| x.1.first()).ok_or(GraphError::NoPath)?;
-        }
-        path.push(start.clone());
-        Ok(path.into_iter().rev().collect_vec())
+        self.astar(start, end, |_| C::default()).map(|(_, path)| path)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_rejects_unknown_vertices() {
+        let mut g = Graph::<u8, u32>::new();
+        g.add_vertex(1);
+        g.add_vertex(2);
+        assert!(g.add_undirected_edge(1, 2, 5).is_ok());
+        assert!(matches!(g.add_edge(1, 3, 1), Err(GraphError::UnknownVertex)));
+        assert!(matches!(g.add_edge(3, 1, 1), Err(GraphError::UnknownVertex)));
+        g.add_edge(1, 2, 7).unwrap();
+        assert_eq!(g.edges[&1].len(), 1);
+        assert_eq!(g.cost(&1, &2), Some(&7));
+        assert_eq!(g.cost(&2, &1), Some(&5));
+    }
+
+    #[test]
+    fn grid_graph() {
+        let grid = Grid::char_grid("S.#\n#..\n..E");
+        let g = Graph::from_grid(&grid, |&c| c != '#', 1);
+        assert_eq!(g.vertices.len(), 7);
+        assert_eq!(g.find_path(Point::new(0, 0), Point::new(2, 2)).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn goal_directed_searches() {
+        let grid = Grid::char_grid("S...\n.##.\n...E");
+        let g = Graph::from_grid(&grid, |&c| c != '#', 1);
+        let (s, e) = (Point::new(0, 0), Point::new(3, 2));
+        let (cost, path) = g.astar(s, e, |p| p.manhattan_distance(e)).unwrap();
+        assert_eq!((cost, path.len()), (5, 6));
+        let (nearest, cost, _) = g.dijkstra_to_any(s, &[e, Point::new(3, 0)]).unwrap();
+        assert_eq!((nearest, cost), (Point::new(3, 0), 3));
+        let all = g.dijkstra_to_all(s, &[e, Point::new(3, 0), Point::new(1, 1)]);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[&e].0, 5);
+    }
+}
@@ -0,0 +1,278 @@
+use std::{collections::{HashMap, HashSet, VecDeque}, hash::Hash, ops::Add};
+
+use itertools::Itertools;
+
+use super::{Edge, Graph};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<V>(pub Vec<V>);
+
+const UNSEEN: usize = usize::MAX;
+
+impl<V:Eq+Hash+Clone, C> Graph<V,C> {
+    fn index(&self) -> HashMap<&V, usize> {
+        self.vertices.iter().enumerate().map(|(i, v)| (v, i)).collect()
+    }
+
+    fn directed_adjacency(&self) -> Vec<Vec<usize>> {
+        let idx = self.index();
+        self.vertices.iter()
+            .map(|v| self.neighbors(v).filter_map(|(w, _)| idx.get(w).copied()).collect())
+            .collect()
+    }
+
+    pub(super) fn undirected_adjacency(&self) -> Vec<Vec<usize>> {
+        let mut adj = vec![Vec::new(); self.vertices.len()];
+        for (v, ns) in self.directed_adjacency().into_iter().enumerate() {
+            for n in ns.into_iter().filter(|&n| n != v) {
+                adj[v].push(n);
+                adj[n].push(v);
+            }
+        }
+        adj.into_iter().map(|ns| ns.into_iter().unique().collect()).collect()
+    }
+
+    fn labels(&self, ids: impl IntoIterator<Item = usize>) -> Vec<V> {
+        ids.into_iter().map(|i| self.vertices[i].clone()).collect()
+    }
+
+    pub fn connected_components(&self) -> Vec<Vec<V>> {
+        let adj = self.undirected_adjacency();
+        let mut seen = vec![false; adj.len()];
+        let mut components = Vec::new();
+        for s in 0..adj.len() {
+            if seen[s] { continue; }
+            seen[s] = true;
+            let mut component = vec![s];
+            let mut i = 0;
+            while let Some(&v) = component.get(i) {
+                i += 1;
+                for &n in &adj[v] {
+                    if !seen[n] { seen[n] = true; component.push(n); }
+                }
+            }
+            components.push(self.labels(component));
+        }
+        components
+    }
+
+    // Tarjan's algorithm; components come out in reverse topological order of the condensation.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<V>> {
+        let adj = self.directed_adjacency();
+        let n = adj.len();
+        let (mut index, mut low, mut on_stack) = (vec![UNSEEN; n], vec![0; n], vec![false; n]);
+        let (mut counter, mut stack, mut components) = (0, Vec::new(), Vec::new());
+        for s in 0..n {
+            if index[s] != UNSEEN { continue; }
+            let mut calls = vec![(s, 0)];
+            index[s] = counter; low[s] = counter; counter += 1;
+            stack.push(s); on_stack[s] = true;
+            while let Some((v, i)) = calls.last_mut() {
+                let v = *v;
+                if let Some(&w) = adj[v].get(*i) {
+                    *i += 1;
+                    if index[w] == UNSEEN {
+                        index[w] = counter; low[w] = counter; counter += 1;
+                        stack.push(w); on_stack[w] = true;
+                        calls.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some(&(u, _)) = calls.last() { low[u] = low[u].min(low[v]); }
+                if low[v] == index[v] {
+                    let mut component = Vec::new();
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == v { break; }
+                    }
+                    components.push(self.labels(component));
+                }
+            }
+        }
+        components
+    }
+
+    pub fn find_cycle(&self) -> Option<Cycle<V>> {
+        let adj = self.directed_adjacency();
+        // 0 - unvisited, 1 - on the current dfs path, 2 - done
+        let mut state = vec![0u8; adj.len()];
+        for s in 0..adj.len() {
+            if state[s] != 0 { continue; }
+            let mut calls = vec![(s, 0)];
+            state[s] = 1;
+            while let Some((v, i)) = calls.last_mut() {
+                let v = *v;
+                match adj[v].get(*i) {
+                    Some(&w) => {
+                        *i += 1;
+                        if state[w] == 1 {
+                            let from = calls.iter().position(|&(u, _)| u == w).unwrap();
+                            return Some(Cycle(self.labels(calls[from..].iter().map(|&(u, _)| u))));
+                        }
+                        if state[w] == 0 { state[w] = 1; calls.push((w, 0)); }
+                    }
+                    None => { state[v] = 2; calls.pop(); }
+                }
+            }
+        }
+        None
+    }
+
+    // Kahn's algorithm, ties broken by vertex insertion order.
+    pub fn topological_sort(&self) -> Result<Vec<V>, Cycle<V>> {
+        let adj = self.directed_adjacency();
+        let mut indegree = vec![0; adj.len()];
+        adj.iter().flatten().for_each(|&w| indegree[w] += 1);
+        let mut q = (0..adj.len()).filter(|&v| indegree[v] == 0).collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(adj.len());
+        while let Some(v) = q.pop_front() {
+            order.push(v);
+            for &w in &adj[v] {
+                indegree[w] -= 1;
+                if indegree[w] == 0 { q.push_back(w); }
+            }
+        }
+        if order.len() < adj.len() { return Err(self.find_cycle().expect("unsorted vertices lie on a cycle")); }
+        Ok(self.labels(order))
+    }
+
+    pub fn bipartition(&self) -> Option<(Vec<V>, Vec<V>)> {
+        // the adjacency drops self loops, which are odd cycles too
+        if self.vertices.iter().any(|v| self.neighbors(v).any(|(w, _)| w == v)) { return None; }
+        let adj = self.undirected_adjacency();
+        let mut side = vec![None; adj.len()];
+        for s in 0..adj.len() {
+            if side[s].is_some() { continue; }
+            side[s] = Some(false);
+            let mut q = VecDeque::from([s]);
+            while let Some(v) = q.pop_front() {
+                let other = side[v].map(|b| !b);
+                for &w in &adj[v] {
+                    match side[w] {
+                        None => { side[w] = other; q.push_back(w); }
+                        Some(b) if Some(b) != other => return None,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        let (left, right): (Vec<_>, Vec<_>) = (0..adj.len()).partition(|&v| side[v] == Some(false));
+        Some((self.labels(left), self.labels(right)))
+    }
+
+    pub fn is_bipartite(&self) -> bool {
+        self.bipartition().is_some()
+    }
+
+    fn cut_structure(&self) -> (Vec<usize>, Vec<(usize, usize)>) {
+        let adj = self.undirected_adjacency();
+        let n = adj.len();
+        let (mut disc, mut low) = (vec![UNSEEN; n], vec![0; n]);
+        let (mut time, mut points, mut bridges) = (0, HashSet::new(), Vec::new());
+        for root in 0..n {
+            if disc[root] != UNSEEN { continue; }
+            disc[root] = time; low[root] = time; time += 1;
+            let mut calls = vec![(root, UNSEEN, 0)];
+            let mut root_children = 0;
+            while let Some((v, parent, i)) = calls.last_mut() {
+                let (v, parent) = (*v, *parent);
+                if let Some(&w) = adj[v].get(*i) {
+                    *i += 1;
+                    if w == parent { continue; }
+                    if disc[w] == UNSEEN {
+                        disc[w] = time; low[w] = time; time += 1;
+                        calls.push((w, v, 0));
+                    } else {
+                        low[v] = low[v].min(disc[w]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if parent == UNSEEN { continue; }
+                low[parent] = low[parent].min(low[v]);
+                if low[v] > disc[parent] { bridges.push((parent, v)); }
+                if parent == root { root_children += 1; }
+                else if low[v] >= disc[parent] { points.insert(parent); }
+            }
+            if root_children > 1 { points.insert(root); }
+        }
+        (points.into_iter().sorted().collect(), bridges)
+    }
+
+    pub fn articulation_points(&self) -> Vec<V> {
+        self.labels(self.cut_structure().0)
+    }
+
+    pub fn bridges(&self) -> Vec<Edge<V>> {
+        self.cut_structure().1.into_iter()
+            .map(|(a, b)| Edge::new(self.vertices[a].clone(), self.vertices[b].clone()))
+            .collect()
+    }
+}
+
+impl<V:Eq+Hash+Clone, C:Default+Copy+Ord+Add<Output = C>> Graph<V,C> {
+    pub fn minimum_spanning_tree(&self) -> (C, Vec<Edge<V>>) {
+        let idx = self.index();
+        let mut parent = (0..self.vertices.len()).collect_vec();
+        fn root(parent: &mut [usize], mut v: usize) -> usize {
+            while parent[v] != v { parent[v] = parent[parent[v]]; v = parent[v]; }
+            v
+        }
+        let mut total = C::default();
+        let mut tree = Vec::new();
+        for (e, &c) in self.costs.iter().sorted_by_key(|(e, &c)| (c, idx[&e.from], idx[&e.to])) {
+            let (a, b) = (root(&mut parent, idx[&e.from]), root(&mut parent, idx[&e.to]));
+            if a == b { continue; }
+            parent[a] = b;
+            total = total + c;
+            tree.push(e.clone());
+        }
+        (total, tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(n: u8, edges: &[(u8, u8, u32)]) -> Graph<u8, u32> {
+        Graph::from_edges(0..n, edges.iter().copied()).unwrap()
+    }
+
+    #[test]
+    fn components_and_order() {
+        let g = graph(6, &[(0, 1, 1), (1, 2, 1), (2, 0, 1), (2, 3, 1), (4, 5, 1)]);
+        assert_eq!(g.connected_components().len(), 2);
+        let sccs = g.strongly_connected_components();
+        assert_eq!(sccs.len(), 4);
+        assert!(sccs.iter().any(|c| c.iter().sorted().eq(&[0, 1, 2])));
+        let Cycle(cycle) = g.topological_sort().unwrap_err();
+        assert_eq!(cycle.iter().sorted().collect_vec(), vec![&0, &1, &2]);
+        let dag = graph(4, &[(3, 1, 1), (1, 0, 1), (3, 2, 1), (2, 0, 1)]);
+        assert_eq!(dag.topological_sort().unwrap(), vec![3, 1, 2, 0]);
+        assert!(dag.find_cycle().is_none());
+    }
+
+    #[test]
+    fn undirected_structure() {
+        let mut g = graph(5, &[]);
+        for (a, b, c) in [(0, 1, 4), (1, 2, 1), (2, 0, 2), (2, 3, 7), (3, 4, 3)] {
+            g.add_undirected_edge(a, b, c).unwrap();
+        }
+        assert_eq!(g.minimum_spanning_tree().0, 13);
+        assert_eq!(g.articulation_points(), vec![2, 3]);
+        assert_eq!(g.bridges().len(), 2);
+        assert!(!g.is_bipartite());
+        let square = graph(4, &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 0, 1)]);
+        let (a, b) = square.bipartition().unwrap();
+        assert_eq!((a, b), (vec![0, 2], vec![1, 3]));
+        let looped = graph(4, &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 0, 1), (2, 2, 1)]);
+        assert!(!looped.is_bipartite());
+        let bare = Graph::<u8, u32> { vertices: vec![0, 1], edges: HashMap::new(), costs: HashMap::new() };
+        assert_eq!(bare.connected_components().len(), 2);
+    }
+}
@@ -0,0 +1,208 @@
+use std::hash::Hash;
+
+use super::Graph;
+
+// Simple undirected graph over dense ids `0..n` with sorted neighbour lists,
+// so set operations in the clique searches are linear merges instead of hashing.
+#[derive(Debug, Clone, Default)]
+pub struct AdjacencyGraph {
+    adj: Vec<Vec<usize>>
+}
+
+fn intersect(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let (mut i, mut j, mut r) = (0, 0, Vec::new());
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => { r.push(a[i]); i += 1; j += 1; }
+        }
+    }
+    r
+}
+
+fn insert_sorted(s: &mut Vec<usize>, v: usize) {
+    if let Err(i) = s.binary_search(&v) { s.insert(i, v); }
+}
+
+fn remove_sorted(s: &mut Vec<usize>, v: usize) {
+    if let Ok(i) = s.binary_search(&v) { s.remove(i); }
+}
+
+impl AdjacencyGraph {
+    pub fn new(n: usize) -> Self {
+        AdjacencyGraph { adj: vec![Vec::new(); n] }
+    }
+
+    pub fn from_edges(n: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut g = Self::new(n);
+        edges.into_iter().for_each(|(a, b)| g.add_edge(a, b));
+        g
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize) {
+        if a == b { return; }
+        let n = a.max(b) + 1;
+        if self.adj.len() < n { self.adj.resize(n, Vec::new()); }
+        insert_sorted(&mut self.adj[a], b);
+        insert_sorted(&mut self.adj[b], a);
+    }
+
+    pub fn len(&self) -> usize { self.adj.len() }
+
+    pub fn is_empty(&self) -> bool { self.adj.is_empty() }
+
+    pub fn neighbors(&self, v: usize) -> &[usize] { &self.adj[v] }
+
+    pub fn is_adjacent(&self, a: usize, b: usize) -> bool {
+        self.adj[a].binary_search(&b).is_ok()
+    }
+
+    pub fn degeneracy_order(&self) -> Vec<usize> {
+        let n = self.len();
+        let mut degree = self.adj.iter().map(|ns| ns.len()).collect::<Vec<_>>();
+        let max = degree.iter().copied().max().unwrap_or(0);
+        let mut buckets = vec![Vec::new(); max + 1];
+        (0..n).for_each(|v| buckets[degree[v]].push(v));
+        let mut removed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        let mut d = 0;
+        while order.len() < n {
+            let Some(v) = buckets[d].pop() else { d += 1; continue; };
+            if removed[v] || degree[v] != d { continue; }
+            removed[v] = true;
+            order.push(v);
+            for &w in &self.adj[v] {
+                if removed[w] { continue; }
+                degree[w] -= 1;
+                buckets[degree[w]].push(w);
+            }
+            d = d.saturating_sub(1);
+        }
+        order
+    }
+
+    pub fn maximal_cliques(&self) -> MaximalCliques<'_> {
+        MaximalCliques { g: self, order: self.degeneracy_order(), position: Vec::new(), next: 0, stack: Vec::new() }
+    }
+
+    pub fn maximum_clique(&self) -> Vec<usize> {
+        self.maximal_cliques().max_by_key(|c| c.len()).unwrap_or_default()
+    }
+
+    pub fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        (0..self.len()).flat_map(move |a| self.adj[a].iter()
+            .filter(move |&&b| b > a)
+            .flat_map(move |&b| intersect(&self.adj[a], &self.adj[b]).into_iter()
+                .filter(move |&c| c > b)
+                .map(move |c| [a, b, c])))
+    }
+
+    pub fn k_cliques(&self, k: usize) -> Vec<Vec<usize>> {
+        if k == 0 { return vec![Vec::new()]; }
+        let mut res = Vec::new();
+        let mut stack = (0..self.len()).rev()
+            .map(|v| (vec![v], self.adj[v].iter().copied().filter(|&w| w > v).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        while let Some((clique, candidates)) = stack.pop() {
+            if clique.len() == k { res.push(clique); continue; }
+            if clique.len() + candidates.len() < k { continue; }
+            for &v in candidates.iter().rev() {
+                let mut c = clique.clone();
+                c.push(v);
+                let next = intersect(&candidates, &self.adj[v]).into_iter().filter(|&w| w > v).collect();
+                stack.push((c, next));
+            }
+        }
+        res
+    }
+}
+
+struct Frame {
+    r: Vec<usize>,
+    p: Vec<usize>,
+    x: Vec<usize>,
+    candidates: Vec<usize>,
+    next: usize
+}
+
+pub struct MaximalCliques<'a> {
+    g: &'a AdjacencyGraph,
+    order: Vec<usize>,
+    position: Vec<usize>,
+    next: usize,
+    stack: Vec<Frame>
+}
+
+impl MaximalCliques<'_> {
+    fn frame(&self, r: Vec<usize>, p: Vec<usize>, x: Vec<usize>) -> Frame {
+        let pivot = p.iter().chain(&x)
+            .max_by_key(|&&u| intersect(&p, self.g.neighbors(u)).len())
+            .copied();
+        let candidates = match pivot {
+            Some(u) => p.iter().copied().filter(|&v| !self.g.is_adjacent(u, v)).collect(),
+            None => Vec::new()
+        };
+        Frame { r, p, x, candidates, next: 0 }
+    }
+}
+
+impl Iterator for MaximalCliques<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position.is_empty() {
+            self.position = vec![0; self.g.len()];
+            self.order.iter().enumerate().for_each(|(i, &v)| self.position[v] = i);
+        }
+        loop {
+            if let Some(top) = self.stack.last_mut() {
+                let Some(&v) = top.candidates.get(top.next) else { self.stack.pop(); continue; };
+                top.next += 1;
+                let ns = self.g.neighbors(v);
+                let (p, x) = (intersect(&top.p, ns), intersect(&top.x, ns));
+                remove_sorted(&mut top.p, v);
+                insert_sorted(&mut top.x, v);
+                let mut r = top.r.clone();
+                insert_sorted(&mut r, v);
+                if p.is_empty() && x.is_empty() { return Some(r); }
+                if !p.is_empty() { let f = self.frame(r, p, x); self.stack.push(f); }
+            } else {
+                let &v = self.order.get(self.next)?;
+                self.next += 1;
+                let (p, x): (Vec<_>, Vec<_>) = self.g.neighbors(v).iter()
+                    .partition(|&&w| self.position[w] > self.position[v]);
+                if p.is_empty() && x.is_empty() { return Some(vec![v]); }
+                if !p.is_empty() { let f = self.frame(vec![v], p, x); self.stack.push(f); }
+            }
+        }
+    }
+}
+
+impl<V:Eq+Hash+Clone, C> Graph<V,C> {
+    pub fn adjacency_graph(&self) -> AdjacencyGraph {
+        AdjacencyGraph { adj: self.undirected_adjacency().into_iter().map(|mut ns| { ns.sort_unstable(); ns }).collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn cliques() {
+        // two 4-cliques sharing vertex 3, plus a pendant vertex 7
+        let mut g = AdjacencyGraph::new(8);
+        for c in [[0, 1, 2, 3], [3, 4, 5, 6]] {
+            c.iter().tuple_combinations().for_each(|(&a, &b)| g.add_edge(a, b));
+        }
+        g.add_edge(6, 7);
+        let cliques = g.maximal_cliques().sorted().collect_vec();
+        assert_eq!(cliques, vec![vec![0, 1, 2, 3], vec![3, 4, 5, 6], vec![6, 7]]);
+        assert_eq!(g.maximum_clique().len(), 4);
+        assert_eq!(g.triangles().count(), 8);
+        assert_eq!(g.k_cliques(4).len(), 2);
+        assert_eq!(g.k_cliques(2).len(), 13);
+    }
+}
@@ -0,0 +1,257 @@
+use std::{collections::{HashMap, HashSet}, fmt::Write, fs, hash::Hash, io, path::Path};
+
+use super::{labelled::LabelledGraph, Edge, Graph};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format { Dot, GraphMl, Mermaid }
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Style {
+    pub color: Option<String>,
+    pub fill: Option<String>,
+    // Graphviz shape name; Mermaid understands `box`, `circle` and `diamond`.
+    pub shape: Option<String>,
+    pub bold: bool
+}
+
+impl Style {
+    pub fn color(c: &str) -> Self {
+        Style { color: Some(c.to_string()), ..Default::default() }
+    }
+
+    fn highlighted() -> Self {
+        Style { color: Some("red".to_string()), bold: true, ..Default::default() }
+    }
+
+    fn is_plain(&self) -> bool { *self == Style::default() }
+}
+
+type VertexFn<'a, V, T> = Box<dyn Fn(&V) -> T + 'a>;
+type EdgeFn<'a, V, C, T> = Box<dyn Fn(&Edge<V>, &C) -> T + 'a>;
+
+pub struct Exporter<'a, V, C> {
+    graph: &'a Graph<V,C>,
+    directed: bool,
+    vertex_label: VertexFn<'a, V, String>,
+    vertex_style: VertexFn<'a, V, Style>,
+    edge_label: EdgeFn<'a, V, C, Option<String>>,
+    edge_style: EdgeFn<'a, V, C, Style>,
+    highlighted_vertices: HashSet<V>,
+    highlighted_edges: HashSet<Edge<V>>
+}
+
+impl<V:Eq+Hash+Clone, C> Graph<V,C> {
+    pub fn export(&self) -> Exporter<'_, V, C> {
+        let index = self.vertices.iter().cloned().enumerate().map(|(i, v)| (v, i)).collect::<HashMap<_,_>>();
+        Exporter {
+            graph: self,
+            directed: true,
+            vertex_label: Box::new(move |v| index[v].to_string()),
+            vertex_style: Box::new(|_| Style::default()),
+            edge_label: Box::new(|_, _| None),
+            edge_style: Box::new(|_, _| Style::default()),
+            highlighted_vertices: HashSet::new(),
+            highlighted_edges: HashSet::new()
+        }
+    }
+}
+
+impl<C> LabelledGraph<C> {
+    pub fn export(&self) -> Exporter<'_, usize, C> {
+        self.graph.export().vertex_label(|&v| self.label(v).to_string())
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+impl<'a, V:Eq+Hash+Clone, C> Exporter<'a, V, C> {
+    pub fn vertex_label(mut self, f: impl Fn(&V) -> String + 'a) -> Self {
+        self.vertex_label = Box::new(f); self
+    }
+
+    pub fn vertex_style(mut self, f: impl Fn(&V) -> Style + 'a) -> Self {
+        self.vertex_style = Box::new(f); self
+    }
+
+    pub fn edge_label(mut self, f: impl Fn(&Edge<V>, &C) -> Option<String> + 'a) -> Self {
+        self.edge_label = Box::new(f); self
+    }
+
+    pub fn edge_style(mut self, f: impl Fn(&Edge<V>, &C) -> Style + 'a) -> Self {
+        self.edge_style = Box::new(f); self
+    }
+
+    pub fn undirected(mut self) -> Self {
+        self.directed = false; self
+    }
+
+    pub fn highlight_vertices(mut self, vs: impl IntoIterator<Item = V>) -> Self {
+        self.highlighted_vertices.extend(vs); self
+    }
+
+    pub fn highlight_path(mut self, path: &[V]) -> Self {
+        self.highlighted_vertices.extend(path.iter().cloned());
+        self.highlighted_edges.extend(path.windows(2).flat_map(|w| {
+            let (a, b) = (w[0].clone(), w[1].clone());
+            [Edge::new(a.clone(), b.clone()), Edge::new(b, a)]
+        }));
+        self
+    }
+
+    fn style_of_vertex(&self, v: &V) -> Style {
+        if self.highlighted_vertices.contains(v) { Style::highlighted() } else { (self.vertex_style)(v) }
+    }
+
+    fn style_of_edge(&self, e: &Edge<V>, c: &C) -> Style {
+        if self.highlighted_edges.contains(e) { Style::highlighted() } else { (self.edge_style)(e, c) }
+    }
+
+    fn edges(&self) -> Vec<(usize, usize, &'a Edge<V>, &'a C)> {
+        let g = self.graph;
+        let index = g.vertices.iter().enumerate().map(|(i, v)| (v, i)).collect::<HashMap<_,_>>();
+        g.vertices.iter()
+            .flat_map(|v| &g.edges[v])
+            .map(|e| (index[&e.from], index[&e.to], e, &g.costs[e]))
+            .filter(|&(a, b, e, _)| self.directed || a <= b || !g.costs.contains_key(&Edge::new(e.to.clone(), e.from.clone())))
+            .collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        fn attrs(label: Option<String>, s: &Style) -> String {
+            let mut a = Vec::new();
+            if let Some(l) = label { a.push(format!("label=\"{}\"", escape_dot(&l))); }
+            if let Some(c) = &s.color { a.push(format!("color=\"{c}\"")); }
+            if let Some(f) = &s.fill { a.push(format!("style=filled, fillcolor=\"{f}\"")); }
+            if let Some(sh) = &s.shape { a.push(format!("shape={sh}")); }
+            if s.bold { a.push("penwidth=3".to_string()); }
+            if a.is_empty() { String::new() } else { format!(" [{}]", a.join(", ")) }
+        }
+        let (kind, arrow) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        let mut out = format!("{kind} G {{\n");
+        for (i, v) in self.graph.vertices.iter().enumerate() {
+            writeln!(out, "    n{i}{}", attrs(Some((self.vertex_label)(v)), &self.style_of_vertex(v))).unwrap();
+        }
+        for (a, b, e, c) in self.edges() {
+            writeln!(out, "    n{a} {arrow} n{b}{}", attrs((self.edge_label)(e, c), &self.style_of_edge(e, c))).unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let keys = [("label", "node"), ("color", "node"), ("fill", "node"), ("shape", "node"), ("label", "edge"), ("color", "edge")];
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        for (name, target) in keys {
+            writeln!(out, "  <key id=\"{target}_{name}\" for=\"{target}\" attr.name=\"{name}\" attr.type=\"string\"/>").unwrap();
+        }
+        let default = if self.directed { "directed" } else { "undirected" };
+        writeln!(out, "  <graph id=\"G\" edgedefault=\"{default}\">").unwrap();
+        let data = |out: &mut String, key: &str, value: &Option<String>| {
+            if let Some(v) = value { writeln!(out, "      <data key=\"{key}\">{}</data>", escape_xml(v)).unwrap(); }
+        };
+        for (i, v) in self.graph.vertices.iter().enumerate() {
+            let s = self.style_of_vertex(v);
+            writeln!(out, "    <node id=\"n{i}\">").unwrap();
+            data(&mut out, "node_label", &Some((self.vertex_label)(v)));
+            data(&mut out, "node_color", &s.color);
+            data(&mut out, "node_fill", &s.fill);
+            data(&mut out, "node_shape", &s.shape);
+            out.push_str("    </node>\n");
+        }
+        for (k, (a, b, e, c)) in self.edges().into_iter().enumerate() {
+            writeln!(out, "    <edge id=\"e{k}\" source=\"n{a}\" target=\"n{b}\">").unwrap();
+            data(&mut out, "edge_label", &(self.edge_label)(e, c));
+            data(&mut out, "edge_color", &self.style_of_edge(e, c).color);
+            out.push_str("    </edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        fn css(s: &Style) -> String {
+            let mut a = Vec::new();
+            if let Some(c) = &s.color { a.push(format!("stroke:{c}")); }
+            if let Some(f) = &s.fill { a.push(format!("fill:{f}")); }
+            if s.bold { a.push("stroke-width:3px".to_string()); }
+            a.join(",")
+        }
+        let mut out = String::from("flowchart LR\n");
+        let mut styles = Vec::new();
+        for (i, v) in self.graph.vertices.iter().enumerate() {
+            let s = self.style_of_vertex(v);
+            let l = escape_mermaid(&(self.vertex_label)(v));
+            let node = match s.shape.as_deref() {
+                Some("circle") => format!("n{i}((\"{l}\"))"),
+                Some("diamond") => format!("n{i}{{\"{l}\"}}"),
+                Some("box") => format!("n{i}[\"{l}\"]"),
+                _ => format!("n{i}(\"{l}\")")
+            };
+            writeln!(out, "    {node}").unwrap();
+            if !s.is_plain() && !css(&s).is_empty() { styles.push(format!("    style n{i} {}", css(&s))); }
+        }
+        let arrow = if self.directed { "-->" } else { "---" };
+        for (k, (a, b, e, c)) in self.edges().into_iter().enumerate() {
+            match (self.edge_label)(e, c) {
+                Some(l) => writeln!(out, "    n{a} {arrow}|\"{}\"| n{b}", escape_mermaid(&l)).unwrap(),
+                None => writeln!(out, "    n{a} {arrow} n{b}").unwrap()
+            }
+            let s = self.style_of_edge(e, c);
+            if !css(&s).is_empty() { styles.push(format!("    linkStyle {k} {}", css(&s))); }
+        }
+        styles.iter().for_each(|s| { out.push_str(s); out.push('\n'); });
+        out
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Dot => self.to_dot(),
+            Format::GraphMl => self.to_graphml(),
+            Format::Mermaid => self.to_mermaid()
+        }
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>, format: Format) -> io::Result<()> {
+        fs::write(path, self.render(format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_all_formats() {
+        let mut g = LabelledGraph::from_edges([("a", "b", 1), ("b", "c", 2)]);
+        g.add_undirected_edge("c", "a\"", 3).unwrap();
+        let e = g.export()
+            .edge_label(|_, c| Some(c.to_string()))
+            .vertex_style(|&v| if v == 0 { Style::color("green") } else { Style::default() })
+            .highlight_path(&[1, 2]);
+        let dot = e.to_dot();
+        assert!(dot.starts_with("digraph G {"));
+        assert!(dot.contains("n0 [label=\"a\", color=\"green\"]"));
+        assert!(dot.contains("n3 [label=\"a\\\"\"]"));
+        assert!(dot.contains("n1 -> n2 [label=\"2\", color=\"red\", penwidth=3]"));
+        assert_eq!(dot.matches("->").count(), 4);
+        let undirected = g.export().undirected().to_dot();
+        assert_eq!(undirected.matches("--").count(), 3);
+        let xml = e.to_graphml();
+        assert!(xml.contains("<data key=\"node_label\">a&quot;</data>"));
+        assert_eq!(xml.matches("<edge ").count(), 4);
+        let mermaid = e.render(Format::Mermaid);
+        assert!(mermaid.contains("n1 -->|\"2\"| n2"));
+        assert!(mermaid.contains("style n0 stroke:green"));
+        assert!(mermaid.contains("linkStyle 1 stroke:red,stroke-width:3px"));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::misc::interner::Interner;
+
+use super::{cliques::AdjacencyGraph, Graph, GraphError};
+
+// Vertex ids double as positions in `graph.vertices`.
+#[derive(Default)]
+pub struct LabelledGraph<C> {
+    pub names: Interner,
+    pub graph: Graph<usize, C>
+}
+
+impl<C> LabelledGraph<C> {
+    pub fn new() -> Self {
+        LabelledGraph { names: Interner::new(), graph: Graph::new() }
+    }
+
+    pub fn add_vertex(&mut self, label: &str) -> usize {
+        let id = self.names.intern(label);
+        self.graph.add_vertex(id);
+        id
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str, cost: C) -> Result<(), GraphError> {
+        let (a, b) = (self.add_vertex(from), self.add_vertex(to));
+        self.graph.add_edge(a, b, cost)
+    }
+
+    pub fn add_undirected_edge(&mut self, a: &str, b: &str, cost: C) -> Result<(), GraphError> 
+    where C: Clone
+    {
+        let (a, b) = (self.add_vertex(a), self.add_vertex(b));
+        self.graph.add_undirected_edge(a, b, cost)
+    }
+
+    pub fn from_edges<'a>(edges: impl IntoIterator<Item = (&'a str, &'a str, C)>) -> Self {
+        let mut g = Self::new();
+        edges.into_iter().for_each(|(a, b, c)| g.add_edge(a, b, c).expect("both ends were just added"));
+        g
+    }
+
+    pub fn from_undirected_edges<'a>(edges: impl IntoIterator<Item = (&'a str, &'a str, C)>) -> Self 
+    where C: Clone
+    {
+        let mut g = Self::new();
+        edges.into_iter().for_each(|(a, b, c)| g.add_undirected_edge(a, b, c).expect("both ends were just added"));
+        g
+    }
+
+    pub fn id(&self, label: &str) -> Option<usize> {
+        self.names.get(label)
+    }
+
+    pub fn label(&self, id: usize) -> &str {
+        self.names.label(id)
+    }
+
+    pub fn adjacency(&self) -> Vec<Vec<usize>> {
+        self.graph.vertices.iter()
+            .map(|v| self.graph.edges[v].iter().map(|e| e.to).collect())
+            .collect()
+    }
+
+    pub fn adjacency_graph(&self) -> AdjacencyGraph {
+        self.graph.adjacency_graph()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_round_trip() {
+        let g = LabelledGraph::from_edges([("kh", "tc", 1), ("qp", "kh", 2), ("kh", "tc", 3)]);
+        let kh = g.id("kh").unwrap();
+        assert_eq!((kh, g.label(kh)), (0, "kh"));
+        assert_eq!(g.adjacency(), vec![vec![1], vec![], vec![0]]);
+        assert_eq!(g.graph.cost(&0, &1), Some(&3));
+        assert!(g.id("xx").is_none());
+    }
+}
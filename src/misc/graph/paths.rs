@@ -0,0 +1,170 @@
+use std::{collections::{HashMap, HashSet}, hash::Hash, ops::Add};
+
+use num::{BigUint, One, Zero};
+use rand::Rng;
+
+use super::Edge;
+
+// Assumes strictly positive edge costs, otherwise equal-cost predecessors may form cycles.
+#[derive(Debug, Clone)]
+pub struct PathDag<V> {
+    pub start: V,
+    pub predecessors: HashMap<V, Vec<V>>
+}
+
+impl<V:Eq+Hash+Clone> PathDag<V> {
+    pub fn new(start: V, mut predecessors: HashMap<V, Vec<V>>) -> Self {
+        predecessors.insert(start.clone(), Vec::new());
+        PathDag { start, predecessors }
+    }
+
+    pub fn from_dijkstra<C>(start: V, visits: &HashMap<&V, Option<(C, Vec<V>)>>) -> Self {
+        let predecessors = visits.iter()
+            .filter_map(|(v, d)| d.as_ref().map(|d| ((*v).clone(), d.1.clone())))
+            .collect();
+        Self::new(start, predecessors)
+    }
+
+    pub fn reaches(&self, end: &V) -> bool {
+        self.predecessors.contains_key(end)
+    }
+
+    pub fn paths_to(&self, end: &V) -> AllPaths<'_, V> {
+        let stack = if self.reaches(end) { vec![(end.clone(), 0)] } else { Vec::new() };
+        AllPaths { dag: self, stack }
+    }
+
+    fn ancestors<'a>(&self, ends: impl IntoIterator<Item = &'a V>) -> Vec<V> where V: 'a {
+        let mut seen = HashSet::new();
+        let mut order = Vec::new();
+        for end in ends.into_iter().filter(|e| self.reaches(e)) {
+            if !seen.insert(end.clone()) { continue; }
+            let mut stack = vec![(end.clone(), 0)];
+            while let Some((v, i)) = stack.last_mut() {
+                match self.predecessors[v].get(*i) {
+                    Some(p) => {
+                        *i += 1;
+                        if seen.insert(p.clone()) { stack.push((p.clone(), 0)); }
+                    }
+                    None => order.push(stack.pop().unwrap().0)
+                }
+            }
+        }
+        order
+    }
+
+    fn counts<N:Zero+One+Clone+Add<Output = N>>(&self, end: &V) -> HashMap<V, N> {
+        let mut counts: HashMap<V, N> = HashMap::new();
+        for v in self.ancestors([end]) {
+            let n = if v == self.start { N::one() } else {
+                self.predecessors[&v].iter().fold(N::zero(), |acc, p| acc + counts[p].clone())
+            };
+            counts.insert(v, n);
+        }
+        counts
+    }
+
+    pub fn count_paths<N:Zero+One+Clone+Add<Output = N>>(&self, end: &V) -> N {
+        self.counts(end).remove(end).unwrap_or(N::zero())
+    }
+
+    pub fn vertices_on_paths<'a>(&self, ends: impl IntoIterator<Item = &'a V>) -> HashSet<V> where V: 'a {
+        self.ancestors(ends).into_iter().collect()
+    }
+
+    pub fn edges_on_paths<'a>(&self, ends: impl IntoIterator<Item = &'a V>) -> HashSet<Edge<V>> where V: 'a {
+        self.ancestors(ends).into_iter()
+            .flat_map(|v| self.predecessors[&v].iter().map(move |p| Edge::new(p.clone(), v.clone())).collect::<Vec<_>>())
+            .collect()
+    }
+
+    pub fn nth_path(&self, end: &V, index: &BigUint) -> Option<Vec<V>> {
+        let counts = self.counts::<BigUint>(end);
+        let mut index = index.clone();
+        if counts.get(end).is_none_or(|n| index >= *n) { return None; }
+        let mut path = vec![end.clone()];
+        let mut cur = end;
+        while *cur != self.start {
+            for p in &self.predecessors[cur] {
+                let n = &counts[p];
+                if index < *n { cur = p; break; }
+                index -= n;
+            }
+            path.push(cur.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn sample_path(&self, end: &V, rng: &mut impl Rng) -> Option<Vec<V>> {
+        let total = self.count_paths::<BigUint>(end);
+        if total.is_zero() { return None; }
+        let bits = total.bits();
+        let index = loop {
+            let mut digits = (0..bits.div_ceil(32)).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+            if !bits.is_multiple_of(32) { *digits.last_mut().unwrap() >>= 32 - bits % 32; }
+            let candidate = BigUint::new(digits);
+            if candidate < total { break candidate; }
+        };
+        self.nth_path(end, &index)
+    }
+}
+
+pub struct AllPaths<'a, V> {
+    dag: &'a PathDag<V>,
+    stack: Vec<(V, usize)>
+}
+
+impl<V:Eq+Hash+Clone> Iterator for AllPaths<'_, V> {
+    type Item = Vec<V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((v, i)) = self.stack.last_mut() {
+            if *v == self.dag.start {
+                let path = self.stack.iter().rev().map(|(v, _)| v.clone()).collect();
+                self.stack.pop();
+                return Some(path);
+            }
+            match self.dag.predecessors[v].get(*i) {
+                Some(p) => {
+                    *i += 1;
+                    let p = p.clone();
+                    if self.dag.reaches(&p) { self.stack.push((p, 0)); }
+                }
+                None => { self.stack.pop(); }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::misc::graph::search;
+
+    fn lattice(n: i32) -> PathDag<(i32,i32)> {
+        search::bfs((0,0), |&(x,y)| [(x+1,y), (x,y+1)].into_iter().filter(move |&(x,y)| x <= n && y <= n), |_| false).path_dag()
+    }
+
+    #[test]
+    fn enumerates_and_counts() {
+        let dag = lattice(2);
+        let paths = dag.paths_to(&(2,2)).collect::<Vec<_>>();
+        assert_eq!(paths.len(), 6);
+        assert!(paths.iter().all(|p| p.len() == 5 && p[0] == (0,0) && p[4] == (2,2)));
+        assert_eq!(dag.count_paths::<u64>(&(2,2)), 6);
+        assert_eq!(dag.nth_path(&(2,2), &BigUint::from(4u8)).as_ref(), paths.get(4));
+        assert_eq!(dag.vertices_on_paths([&(1,0)]).len(), 2);
+        assert_eq!(dag.edges_on_paths([&(1,1)]).len(), 4);
+    }
+
+    #[test]
+    fn big_counts_and_sampling() {
+        let dag = lattice(40);
+        let n = dag.count_paths::<BigUint>(&(40,40));
+        assert_eq!(n.to_string(), "107507208733336176461620");
+        let p = dag.sample_path(&(40,40), &mut rand::thread_rng()).unwrap();
+        assert_eq!(p.len(), 81);
+    }
+}
@@ -0,0 +1,154 @@
+use std::{collections::{BinaryHeap, HashMap, HashSet, VecDeque}, hash::Hash, ops::Add};
+
+use super::{paths::PathDag, DijkstraState};
+
+#[derive(Debug, Clone)]
+pub struct SearchResult<V,C> {
+    pub start: V,
+    pub distances: HashMap<V, C>,
+    pub predecessors: HashMap<V, Vec<V>>,
+    pub goals: Vec<V>
+}
+
+impl<V:Eq+Hash+Clone, C:Copy> SearchResult<V,C> {
+    fn new(start: V, zero: C) -> Self {
+        SearchResult {
+            distances: HashMap::from([(start.clone(), zero)]),
+            predecessors: HashMap::from([(start.clone(), Vec::new())]),
+            start,
+            goals: Vec::new()
+        }
+    }
+
+    pub fn distance(&self, v: &V) -> Option<C> {
+        self.distances.get(v).copied()
+    }
+
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goals.first().and_then(|g| self.distance(g))
+    }
+
+    pub fn parent(&self, v: &V) -> Option<&V> {
+        self.predecessors.get(v).and_then(|ps| ps.first())
+    }
+
+    pub fn path_to(&self, v: &V) -> Option<Vec<V>> {
+        let mut path = vec![v.clone()];
+        let mut cur = v;
+        while *cur != self.start {
+            cur = self.parent(cur)?;
+            path.push(cur.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn path_dag(&self) -> PathDag<V> {
+        PathDag::new(self.start.clone(), self.predecessors.clone())
+    }
+
+    fn relax(&mut self, from: &V, to: V, cost: C) -> bool 
+    where C: Ord
+    {
+        match self.distances.get(&to) {
+            Some(d) if *d < cost => false,
+            Some(d) if *d == cost => { self.predecessors.get_mut(&to).unwrap().push(from.clone()); false }
+            _ => {
+                self.distances.insert(to.clone(), cost);
+                self.predecessors.insert(to, vec![from.clone()]);
+                true
+            }
+        }
+    }
+}
+
+pub fn bfs<V,I>(start: V, successors: impl Fn(&V) -> I, is_goal: impl Fn(&V) -> bool) -> SearchResult<V, usize>
+where V: Eq+Hash+Clone, I: IntoIterator<Item = V>
+{
+    let mut res = SearchResult::new(start.clone(), 0);
+    let mut q = VecDeque::from([start]);
+    while let Some(v) = q.pop_front() {
+        let d = res.distances[&v];
+        if res.goal_cost().is_some_and(|g| d > g) { break; }
+        if is_goal(&v) { res.goals.push(v.clone()); }
+        for n in successors(&v) {
+            if res.relax(&v, n.clone(), d+1) { q.push_back(n); }
+        }
+    }
+    res
+}
+
+pub fn dijkstra<V,C,I>(start: V, successors: impl Fn(&V) -> I, is_goal: impl Fn(&V) -> bool) -> SearchResult<V,C>
+where V: Eq+Ord+Hash+Clone, C: Default+Copy+Ord+Add<Output = C>, I: IntoIterator<Item = (V,C)>
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+pub fn dijkstra_all<V,C,I>(start: V, successors: impl Fn(&V) -> I, targets: &[V]) -> SearchResult<V,C>
+where V: Eq+Ord+Hash+Clone, C: Default+Copy+Ord+Add<Output = C>, I: IntoIterator<Item = (V,C)>
+{
+    let needed = targets.iter().collect::<HashSet<_>>().len();
+    explore(start, successors, |_| C::default(), |v| targets.contains(v), needed)
+}
+
+pub fn astar<V,C,I>(start: V, successors: impl Fn(&V) -> I, heuristic: impl Fn(&V) -> C, is_goal: impl Fn(&V) -> bool) -> SearchResult<V,C>
+where V: Eq+Ord+Hash+Clone, C: Default+Copy+Ord+Add<Output = C>, I: IntoIterator<Item = (V,C)>
+{
+    explore(start, successors, heuristic, is_goal, 1)
+}
+
+// Best-first search which, once `needed` distinct goals are settled, only finishes the
+// current cost tier so the predecessor lists of those goals stay complete.
+fn explore<V,C,I>(start: V, successors: impl Fn(&V) -> I, heuristic: impl Fn(&V) -> C, is_goal: impl Fn(&V) -> bool, needed: usize) -> SearchResult<V,C>
+where V: Eq+Ord+Hash+Clone, C: Default+Copy+Ord+Add<Output = C>, I: IntoIterator<Item = (V,C)>
+{
+    let mut res = SearchResult::new(start.clone(), C::default());
+    let mut best = None;
+    let mut q = BinaryHeap::from([DijkstraState { cost: heuristic(&start), position: start }]);
+    while let Some(DijkstraState { position, cost }) = q.pop() {
+        if best.is_some_and(|b| cost > b) { break; }
+        let d = res.distances[&position];
+        if d + heuristic(&position) < cost { continue; }
+        if is_goal(&position) && !res.goals.contains(&position) {
+            res.goals.push(position.clone());
+            if res.goals.len() >= needed && best.is_none() { best = Some(cost); }
+        }
+        for (n, c) in successors(&position) {
+            if res.relax(&position, n.clone(), d + c) {
+                q.push(DijkstraState { cost: d + c + heuristic(&n), position: n });
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let succ = |&v: &i32| [(v+1, 1), (v*2, 1)].into_iter().filter(|&(n,_)| n <= 100);
+        let d = dijkstra(1, succ, |&v| v == 100);
+        let a = astar(1, succ, |&v| if v < 100 { 1 } else { 0 }, |&v| v == 100);
+        assert_eq!(d.goal_cost(), Some(8));
+        assert_eq!(a.goal_cost(), Some(8));
+        assert_eq!(a.path_to(&100).unwrap().len(), 9);
+    }
+
+    #[test]
+    fn bfs_records_all_parents() {
+        let r = bfs((0,0), |&(x,y): &(i32,i32)| [(x+1,y), (x,y+1)].into_iter().filter(|&(x,y)| x <= 2 && y <= 2), |_| false);
+        assert_eq!(r.distance(&(2,2)), Some(4));
+        assert_eq!(r.predecessors[&(1,1)].len(), 2);
+        assert!(r.goals.is_empty());
+    }
+
+    #[test]
+    fn dijkstra_settles_all_targets() {
+        let r = dijkstra_all(0, |&v: &u32| [(v+1, 1u32), (v+3, 2)], &[7, 2, 2]);
+        assert_eq!(r.goals, vec![2, 7]);
+        assert_eq!(r.distance(&7), Some(5));
+        assert!(r.distance(&20).is_none());
+    }
+}
@@ -59,6 +59,13 @@ impl<T> Grid<T> {
     pub fn points_iter(&self) -> GridIter<'_, T> {
         GridIter { grid: self, pos: 0 }
     }
+
+    pub fn render(&self, glyph: impl Fn(&T) -> char) -> String {
+        self.cells.chunks(self.width)
+            .map(|row| row.iter().map(&glyph).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 }
 
 impl<T: PartialEq + 'static> Grid<T> {
@@ -108,6 +115,12 @@ impl<'a,T> Iterator for GridIter<'a, T> {
     }
 }
 
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(|&c| c))
+    }
+}
+
 impl<T:Debug> Debug for Grid<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Grid")
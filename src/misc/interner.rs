@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    ids: HashMap<String, usize>,
+    labels: Vec<String>
+}
+
+impl Interner {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn intern(&mut self, label: &str) -> usize {
+        if let Some(&id) = self.ids.get(label) { return id; }
+        let id = self.labels.len();
+        self.ids.insert(label.to_string(), id);
+        self.labels.push(label.to_string());
+        id
+    }
+
+    pub fn get(&self, label: &str) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: usize) -> &str {
+        &self.labels[id]
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn len(&self) -> usize { self.labels.len() }
+
+    pub fn is_empty(&self) -> bool { self.labels.is_empty() }
+}
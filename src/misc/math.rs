@@ -0,0 +1,28 @@
+use num::{integer::{ExtendedGcd, Integer}, Signed};
+
+// moduli need not be coprime; `None` if the congruences contradict each other
+pub fn crt<T: Integer + Signed + Clone>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences.into_iter().try_fold((T::zero(), T::one()), |(r1, m1), (r2, m2)| {
+        let ExtendedGcd { gcd, x, .. } = m1.extended_gcd(&m2);
+        let diff = r2 - r1.clone();
+        if !diff.is_multiple_of(&gcd) { return None; }
+        let step = m2 / gcd.clone();
+        let k = (diff / gcd * x).mod_floor(&step);
+        let lcm = m1.clone() * step;
+        Some(((r1 + m1 * k).mod_floor(&lcm), lcm))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combines_congruences() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(20, 101), (20, 103)]), Some((20i64, 101*103)));
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt::<i32>([]), Some((0, 1)));
+    }
+}
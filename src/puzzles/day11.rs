@@ -1,26 +1,23 @@
-use std::{collections::HashMap, fs, usize};
+pub mod stones;
+
+use std::{fs, ops::AddAssign};
 
 use itertools::Itertools;
+use num::{One, Zero};
+
+use stones::{multiset, total, Engine};
 
 pub fn solve() {
     let data = fs::read_to_string("../inputs/day11").expect("Should be able to read input");
     let numbers = data.split_whitespace().map(|s| s.parse::<u64>().expect("should be a number")).collect_vec();
-    let mut counts = numbers.into_iter().counts();
-    println!("P1: {p1}", p1 = _solve(25, &mut counts));
-    println!("P2: {p2}", p2 = _solve(50, &mut counts));
-}
-
-fn _solve(cnt:usize, counts: &mut HashMap<u64,usize>) -> usize {
-    (0..cnt).for_each(|_i| { blink_once(counts); });
-    counts.values().sum()
+    println!("P1: {p1}", p1 = _solve::<u64>(&numbers, 25));
+    println!("P2: {p2}", p2 = _solve::<u64>(&numbers, 75));
 }
 
-fn blink_once(counts: &mut HashMap<u64,usize>) {
-        let mut nxt = HashMap::new();
-        counts.iter().for_each(|(&n,&c)| {
-            blink_single(n).iter().for_each(|&x| { *nxt.entry(x).or_insert(0) += c; });
-        });
-        *counts = nxt;
+// Counts outgrow `u64` after a couple hundred blinks; use `BigUint` beyond that.
+fn _solve<C: Clone + Zero + One + for<'a> AddAssign<&'a C>>(numbers:&[u64], blinks:usize) -> C {
+    let mut engine = Engine::new(|&n| blink_single(n));
+    total(&engine.evolve(&multiset(numbers.iter().copied()), blinks))
 }
 
 fn blink_single(n:u64) -> Vec<u64> {
@@ -33,6 +30,7 @@ fn blink_single(n:u64) -> Vec<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num::BigUint;
     
     #[test]
     fn test_blink_num() {
@@ -46,8 +44,9 @@ mod tests {
 
     #[test]
     fn sample_data() {
-        let mut counts = [125, 17].into_iter().counts();
-        assert_eq!(_solve(6, &mut counts), 22);
-        assert_eq!(_solve(19, &mut counts), 55312);
+        assert_eq!(_solve::<u64>(&[125, 17], 6), 22);
+        assert_eq!(_solve::<u64>(&[125, 17], 25), 55312);
+        assert_eq!(_solve::<BigUint>(&[125, 17], 75), BigUint::from(_solve::<u64>(&[125, 17], 75)));
+        assert!(_solve::<BigUint>(&[125, 17], 300).bits() > 64);
     }
 }
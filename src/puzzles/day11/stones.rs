@@ -0,0 +1,73 @@
+use std::{collections::HashMap, hash::Hash, ops::AddAssign};
+
+use num::{One, Zero};
+
+pub type Multiset<V, C> = HashMap<V, C>;
+
+pub fn multiset<V: Eq + Hash, C: Zero + One + for<'a> AddAssign<&'a C>>(values: impl IntoIterator<Item = V>) -> Multiset<V, C> {
+    let mut m = HashMap::new();
+    values.into_iter().for_each(|v| *m.entry(v).or_insert_with(C::zero) += &C::one());
+    m
+}
+
+pub struct Engine<V, R> {
+    rule: R,
+    cache: HashMap<V, Vec<V>>
+}
+
+impl<V: Clone + Eq + Hash, R: Fn(&V) -> Vec<V>> Engine<V, R> {
+    pub fn new(rule: R) -> Self {
+        Engine { rule, cache: HashMap::new() }
+    }
+
+    pub fn cached(&self) -> usize { self.cache.len() }
+
+    pub fn step<C: Clone + Zero + for<'a> AddAssign<&'a C>>(&mut self, counts: &Multiset<V, C>) -> Multiset<V, C> {
+        let mut next = HashMap::new();
+        for (v, c) in counts {
+            let rule = &self.rule;
+            for w in self.cache.entry(v.clone()).or_insert_with(|| rule(v)) {
+                *next.entry(w.clone()).or_insert_with(C::zero) += c;
+            }
+        }
+        next
+    }
+
+    pub fn evolve<C: Clone + Zero + for<'a> AddAssign<&'a C>>(&mut self, initial: &Multiset<V, C>, steps: usize) -> Multiset<V, C> {
+        (0..steps).fold(initial.clone(), |counts, _| self.step(&counts))
+    }
+
+    pub fn totals<C: Clone + Zero + for<'a> AddAssign<&'a C>>(&mut self, initial: &Multiset<V, C>, steps: usize) -> Vec<C> {
+        let mut counts = initial.clone();
+        let mut totals = vec![total(&counts)];
+        for _ in 0..steps {
+            counts = self.step(&counts);
+            totals.push(total(&counts));
+        }
+        totals
+    }
+}
+
+pub fn total<V, C: Zero + for<'a> AddAssign<&'a C>>(counts: &Multiset<V, C>) -> C {
+    let mut t = C::zero();
+    counts.values().for_each(|c| t += c);
+    t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+
+    #[test]
+    fn evolves_any_rule_with_any_counts() {
+        let mut doubling = Engine::new(|&n: &u8| vec![n, n]);
+        let totals = doubling.totals(&multiset::<_, BigUint>([1, 2, 2]), 200);
+        assert_eq!(totals[200], BigUint::from(3u8) << 200);
+        assert_eq!(doubling.cached(), 2);
+        let mut collatz = Engine::new(|&n: &u64| vec![if n % 2 == 0 { n / 2 } else { 3*n + 1 }]);
+        let counts = collatz.evolve(&multiset::<_, u64>(1..=10), 20);
+        assert_eq!(counts.keys().copied().max(), Some(4));
+        assert_eq!(total(&counts), 10);
+    }
+}
@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+use num::{integer::ExtendedGcd, Integer, Signed};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Buttons<T> {
+    pub a: T,
+    pub b: T
+}
+
+impl<T: Integer + Clone> Buttons<T> {
+    pub fn cost(&self, costs: &Buttons<T>) -> T {
+        self.a.clone() * costs.a.clone() + self.b.clone() * costs.b.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine<T> {
+    pub a: (T, T),
+    pub b: (T, T),
+    pub prize: (T, T)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMachineError(pub String);
+
+fn pair<T: FromStr>(line: Option<&str>, prefix: &str, sep: &str) -> Result<(T, T), ParseMachineError> {
+    let err = || ParseMachineError(line.unwrap_or_default().to_string());
+    let (x, y) = line.and_then(|l| l.strip_prefix(prefix)).and_then(|l| l.split_once(sep)).ok_or_else(err)?;
+    Ok((x.parse().map_err(|_| err())?, y.trim_end().parse().map_err(|_| err())?))
+}
+
+impl<T: FromStr> FromStr for Machine<T> {
+    type Err = ParseMachineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let a = pair(lines.next(), "Button A: X+", ", Y+")?;
+        let b = pair(lines.next(), "Button B: X+", ", Y+")?;
+        let prize = pair(lines.next(), "Prize: X=", ", Y=")?;
+        Ok(Machine { a, b, prize })
+    }
+}
+
+fn cross<T: Integer + Clone>(p: &(T, T), q: &(T, T)) -> T {
+    p.0.clone() * q.1.clone() - p.1.clone() * q.0.clone()
+}
+
+fn narrow<T: Integer + Signed + Clone>(range: &mut (Option<T>, Option<T>), base: T, step: T, limit: &Option<T>) -> bool {
+    if step.is_zero() {
+        return !base.is_negative() && limit.as_ref().is_none_or(|l| base <= *l);
+    }
+    let lower = Some(-base.clone());
+    let upper = limit.clone().map(|l| l - base);
+    let (from, to) = if step.is_positive() { (lower, upper) } else { (upper, lower) };
+    if let Some(f) = from.map(|f| f.div_ceil(&step)) {
+        range.0 = Some(range.0.take().map_or(f.clone(), |r| r.max(f)));
+    }
+    if let Some(t) = to.map(|t| t.div_floor(&step)) {
+        range.1 = Some(range.1.take().map_or(t.clone(), |r| r.min(t)));
+    }
+    !matches!(&*range, (Some(lo), Some(hi)) if lo > hi)
+}
+
+impl<T: Integer + Signed + Clone> Machine<T> {
+    pub fn shifted(&self, offset: T) -> Self {
+        let prize = (self.prize.0.clone() + offset.clone(), self.prize.1.clone() + offset);
+        Machine { prize, ..self.clone() }
+    }
+
+    // Collinear buttons can have many solutions, or cheaper ones without end if a cost
+    // is negative, in which case there is no answer either.
+    pub fn cheapest(&self, costs: &Buttons<T>, limits: &Buttons<Option<T>>) -> Option<Buttons<T>> {
+        let d = cross(&self.a, &self.b);
+        if d.is_zero() { return self.cheapest_collinear(costs, limits); }
+        let a = cross(&self.prize, &self.b);
+        let b = cross(&self.a, &self.prize);
+        if !a.is_multiple_of(&d) || !b.is_multiple_of(&d) { return None; }
+        let presses = Buttons { a: a / d.clone(), b: b / d };
+        let within = |n: &T, limit: &Option<T>| !n.is_negative() && limit.as_ref().is_none_or(|l| n <= l);
+        (within(&presses.a, &limits.a) && within(&presses.b, &limits.b)).then_some(presses)
+    }
+
+    // Both buttons move along one line, so only one axis matters: every solution of
+    // `a*u + b*v = w` is `a0 + k*v/g, b0 - k*u/g`, and the cost is linear in `k`.
+    fn cheapest_collinear(&self, costs: &Buttons<T>, limits: &Buttons<Option<T>>) -> Option<Buttons<T>> {
+        let zero = (T::zero(), T::zero());
+        if !cross(&self.a, &self.prize).is_zero() || !cross(&self.b, &self.prize).is_zero() { return None; }
+        if self.a == zero && self.b == zero {
+            return (self.prize == zero).then(|| Buttons { a: T::zero(), b: T::zero() });
+        }
+        let (u, v, w) = if self.a.0.is_zero() && self.b.0.is_zero() {
+            (self.a.1.clone(), self.b.1.clone(), self.prize.1.clone())
+        } else {
+            (self.a.0.clone(), self.b.0.clone(), self.prize.0.clone())
+        };
+        let ExtendedGcd { gcd, x, y } = u.extended_gcd(&v);
+        if !w.is_multiple_of(&gcd) { return None; }
+        let m = w / gcd.clone();
+        let (a0, b0) = (x * m.clone(), y * m);
+        let (p, q) = (v / gcd.clone(), -(u / gcd));
+        let mut range = (None, None);
+        if !narrow(&mut range, a0.clone(), p.clone(), &limits.a) || !narrow(&mut range, b0.clone(), q.clone(), &limits.b) {
+            return None;
+        }
+        let slope = Buttons { a: p.clone(), b: q.clone() }.cost(costs);
+        let k = match (slope.is_positive(), slope.is_negative(), range) {
+            (true, _, (lo, _)) => lo?,
+            (_, true, (_, hi)) => hi?,
+            (_, _, (lo, hi)) => lo.or(hi).unwrap_or_else(T::zero)
+        };
+        Some(Buttons { a: a0 + k.clone() * p, b: b0 + k * q })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigInt;
+
+    fn machine(a: (i64, i64), b: (i64, i64), prize: (i64, i64)) -> Machine<i64> {
+        Machine { a, b, prize }
+    }
+
+    #[test]
+    fn solves_regular_and_collinear_machines() {
+        let (costs, free) = (Buttons { a: 3, b: 1 }, Buttons { a: None, b: None });
+        let m = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400".parse::<Machine<i64>>().unwrap();
+        assert_eq!(m.cheapest(&costs, &free), Some(Buttons { a: 80, b: 40 }));
+        assert_eq!(m.cheapest(&costs, &Buttons { a: Some(79), b: None }), None);
+        assert!("Button A: X+94\nButton B: X+22, Y+67".parse::<Machine<i64>>().is_err());
+
+        let line = machine((2, 2), (1, 1), (10, 10));
+        assert_eq!(line.cheapest(&costs, &free), Some(Buttons { a: 0, b: 10 }));
+        assert_eq!(line.cheapest(&Buttons { a: 1, b: 3 }, &free), Some(Buttons { a: 5, b: 0 }));
+        assert_eq!(line.cheapest(&costs, &Buttons { a: None, b: Some(5) }), Some(Buttons { a: 3, b: 4 }));
+        assert_eq!(line.cheapest(&costs, &Buttons { a: Some(2), b: Some(5) }), None);
+        assert_eq!(machine((1, 1), (2, 2), (3, 4)).cheapest(&costs, &free), None);
+        assert_eq!(machine((4, 2), (6, 3), (10, 5)).cheapest(&costs, &free), Some(Buttons { a: 1, b: 1 }));
+        assert_eq!(machine((4, 2), (6, 3), (2, 1)).cheapest(&costs, &free), None);
+        assert_eq!(machine((0, 3), (0, 5), (0, 7)).cheapest(&costs, &free), None);
+        assert_eq!(machine((0, 3), (0, 5), (0, 8)).cheapest(&costs, &free), Some(Buttons { a: 1, b: 1 }));
+        assert_eq!(machine((0, 0), (0, 0), (0, 0)).cheapest(&costs, &free), Some(Buttons { a: 0, b: 0 }));
+        assert_eq!(machine((3, 0), (-2, 0), (1, 0)).cheapest(&Buttons { a: -1, b: 0 }, &free), None);
+        assert_eq!(machine((3, 0), (-2, 0), (1, 0)).cheapest(&costs, &free), Some(Buttons { a: 1, b: 1 }));
+    }
+
+    #[test]
+    fn works_with_big_integers() {
+        let (a, b) = (BigInt::from(10).pow(20), BigInt::from(10).pow(21) + 7);
+        let m = Machine { a: (26.into(), 66.into()), b: (67.into(), 21.into()), prize: (26 * &a + 67 * &b, 66 * &a + 21 * &b) };
+        let presses = m.cheapest(&Buttons { a: 3.into(), b: 1.into() }, &Buttons { a: None, b: None });
+        assert_eq!(presses, Some(Buttons { a, b }));
+        let line = Machine { a: (2.into(), 2.into()), b: (1.into(), 1.into()), prize: (BigInt::from(10).pow(40), BigInt::from(10).pow(40)) };
+        assert_eq!(line.cheapest(&Buttons { a: 1.into(), b: 3.into() }, &Buttons { a: None, b: None }).map(|p| p.b), Some(BigInt::from(0)));
+    }
+}
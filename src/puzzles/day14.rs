@@ -1,8 +1,12 @@
-use std::{fs, str::FromStr};
+pub mod swarm;
+
+use std::fs;
 
 use itertools::Itertools;
 use solutions::misc::point::Point;
 
+use swarm::{Robot, Swarm};
+
 pub fn solve() {
     let input = fs::read_to_string("../inputs/day14").expect("Should be able to read input");
     let data = parse(&input);
@@ -10,38 +14,6 @@ pub fn solve() {
     println!("P2: {p2}", p2 = part2(&data, 101, 103));
 }
 
-#[derive(Debug)]
-struct Robot {
-    position: Point,
-    velocity: Point
-}
-
-impl Robot {
-    fn step(&self, seconds: usize, w:usize, h:usize) -> Point {
-        (self.position + seconds*self.velocity).wrap(w, h)
-    }
-}
-
-#[derive(Debug)]
-struct ParseRobotError;
-
-impl FromStr for Robot {
-    type Err = ParseRobotError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (ps, vs) = s.split_once(" ").ok_or(ParseRobotError)?;
-        let (psx,psy) = ps.strip_prefix("p=").and_then(|s| s.split_once(",")).ok_or(ParseRobotError)?;
-        let (vsx,vsy) = vs.strip_prefix("v=").and_then(|s| s.split_once(",")).ok_or(ParseRobotError)?;
-        let px = psx.parse().map_err(|_| ParseRobotError)?;
-        let py = psy.parse().map_err(|_| ParseRobotError)?;
-        let vx = vsx.parse().map_err(|_| ParseRobotError)?;
-        let vy = vsy.parse().map_err(|_| ParseRobotError)?;
-        let p = Point::new(px, py);
-        let v = Point::new(vx, vy);
-        Ok(Robot {position:p, velocity: v})
-    }
-}
-
 fn parse(input:&String) -> Vec<Robot> {
     input.lines().map(|l| l.parse().unwrap()).collect_vec()
 }
@@ -50,12 +22,10 @@ fn part1(data:&Vec<Robot>, w: usize, h:usize) -> usize {
     quadrant_count_product(data, w, h, 100)
 }
 
-fn part2(data:&Vec<Robot>, w:usize, h:usize) -> usize {
-    (1..=101*103)
-        .map(|t| (t, quadrant_count_product(data, w, h, t)))
-        .min_by(|&x,&y| x.1.cmp(&y.1))
-        .unwrap()
-    .0
+const MIN_TREE_RUN: usize = 10;
+
+fn part2(data:&[Robot], w:usize, h:usize) -> usize {
+    Swarm::new(data.to_vec(), w, h).find_picture(MIN_TREE_RUN).expect("Robots should form a picture")
 }
 
 fn quadrant_count_product(data:&Vec<Robot>, w:usize, h:usize, seconds:usize) -> usize {
@@ -0,0 +1,199 @@
+use std::{collections::HashSet, str::FromStr};
+
+use itertools::Itertools;
+use num::Integer;
+use solutions::misc::{grid::Grid, math::crt, point::Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Robot {
+    pub position: Point,
+    pub velocity: Point
+}
+
+impl Robot {
+    pub fn step(&self, seconds: usize, w:usize, h:usize) -> Point {
+        (self.position + seconds*self.velocity).wrap(w, h)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseRobotError;
+
+impl FromStr for Robot {
+    type Err = ParseRobotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ps, vs) = s.split_once(" ").ok_or(ParseRobotError)?;
+        let (psx,psy) = ps.strip_prefix("p=").and_then(|s| s.split_once(",")).ok_or(ParseRobotError)?;
+        let (vsx,vsy) = vs.strip_prefix("v=").and_then(|s| s.split_once(",")).ok_or(ParseRobotError)?;
+        let px = psx.parse().map_err(|_| ParseRobotError)?;
+        let py = psy.parse().map_err(|_| ParseRobotError)?;
+        let vx = vsx.parse().map_err(|_| ParseRobotError)?;
+        let vy = vsy.parse().map_err(|_| ParseRobotError)?;
+        let p = Point::new(px, py);
+        let v = Point::new(vx, vy);
+        Ok(Robot {position:p, velocity: v})
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detector {
+    Variance,
+    Cluster,
+    Entropy,
+    LongestRun
+}
+
+const ENTROPY_BLOCK: i32 = 4;
+
+pub struct Swarm {
+    pub robots: Vec<Robot>,
+    pub width: usize,
+    pub height: usize
+}
+
+fn variance(values: impl Iterator<Item = i32>) -> f64 {
+    let vs = values.map(f64::from).collect_vec();
+    let mean = vs.iter().sum::<f64>() / vs.len() as f64;
+    vs.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / vs.len() as f64
+}
+
+fn wrap_axis(p: i32, v: i32, t: usize, size: usize) -> i32 {
+    (p as i64 + v as i64 * t as i64).rem_euclid(size as i64) as i32
+}
+
+impl Swarm {
+    pub fn new(robots: Vec<Robot>, width: usize, height: usize) -> Self {
+        Swarm { robots, width, height }
+    }
+
+    pub fn period(&self) -> usize { self.width.lcm(&self.height) }
+
+    pub fn positions(&self, t: usize) -> Vec<Point> {
+        self.robots.iter().map(|r| r.step(t, self.width, self.height)).collect()
+    }
+
+    // x positions only depend on `t mod width` and y positions on `t mod height`,
+    // so each axis is searched separately for its most clustered time.
+    pub fn axis_minima(&self) -> (usize, usize) {
+        let best = |size: usize, axis: fn(&Point) -> i32| (0..size)
+            .map(|t| (t, variance(self.robots.iter().map(|r| wrap_axis(axis(&r.position), axis(&r.velocity), t, size)))))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(0, |(t, _)| t);
+        (best(self.width, |p| p.x), best(self.height, |p| p.y))
+    }
+
+    // t = 0 is reported as the full period, matching `best` which searches 1..=period
+    pub fn crt_time(&self) -> Option<usize> {
+        let (tx, ty) = self.axis_minima();
+        crt([(tx as i64, self.width as i64), (ty as i64, self.height as i64)])
+            .map(|(t, lcm)| if t == 0 { lcm } else { t } as usize)
+    }
+
+    pub fn longest_run(&self, t: usize) -> usize {
+        self.positions(t).into_iter()
+            .unique()
+            .sorted_by_key(|p| (p.y, p.x))
+            .tuple_windows()
+            .fold((1, 1), |(best, run), (a, b)| {
+                let run = if b.y == a.y && b.x == a.x + 1 { run + 1 } else { 1 };
+                (best.max(run), run)
+            })
+            .0
+            .min(self.robots.len())
+    }
+
+    pub fn largest_cluster(&self, t: usize) -> usize {
+        let mut unseen = self.positions(t).into_iter().collect::<HashSet<_>>();
+        let mut best = 0;
+        while let Some(&p) = unseen.iter().next() {
+            unseen.remove(&p);
+            let mut stack = vec![p];
+            let mut size = 0;
+            while let Some(q) = stack.pop() {
+                size += 1;
+                stack.extend(q.ortho_neighbors().into_iter().filter(|n| unseen.remove(n)));
+            }
+            best = best.max(size);
+        }
+        best
+    }
+
+    pub fn entropy(&self, t: usize) -> f64 {
+        let n = self.robots.len() as f64;
+        self.positions(t).iter()
+            .map(|p| (p.x / ENTROPY_BLOCK, p.y / ENTROPY_BLOCK))
+            .counts()
+            .values()
+            .map(|&c| { let q = c as f64 / n; -q * q.log2() })
+            .sum()
+    }
+
+    pub fn score(&self, t: usize, detector: Detector) -> f64 {
+        match detector {
+            Detector::Variance => {
+                let ps = self.positions(t);
+                variance(ps.iter().map(|p| p.x)) + variance(ps.iter().map(|p| p.y))
+            }
+            Detector::Cluster => -(self.largest_cluster(t) as f64),
+            Detector::Entropy => self.entropy(t),
+            Detector::LongestRun => -(self.longest_run(t) as f64)
+        }
+    }
+
+    pub fn best(&self, detector: Detector) -> (usize, f64) {
+        (1..=self.period())
+            .map(|t| (t, self.score(t, detector)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("period is at least one second")
+    }
+
+    pub fn find_picture(&self, min_run: usize) -> Option<usize> {
+        self.crt_time()
+            .filter(|&t| self.longest_run(t) >= min_run)
+            .or_else(|| Some(self.best(Detector::LongestRun).0).filter(|&t| self.longest_run(t) >= min_run))
+    }
+
+    pub fn render(&self, t: usize) -> String {
+        let mut g = Grid::<char>::new(self.width, self.height, '.');
+        self.positions(t).iter().for_each(|p| g[p] = '#');
+        g.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn square(t: i32) -> Swarm {
+        let (w, h) = (31, 29);
+        let mut rng = StdRng::seed_from_u64(14);
+        let robots = (0..16).map(|i| {
+            let target = Point::new(4 + i % 4, i / 4);
+            let velocity = Point::new(rng.gen_range(-10..=10), rng.gen_range(-10..=10));
+            Robot { position: (target + (-velocity) * t).wrap(w, h), velocity }
+        })
+        .collect();
+        Swarm::new(robots, w, h)
+    }
+
+    #[test]
+    fn detectors_agree_on_picture() {
+        let s = square(20);
+        assert_eq!(s.period(), 899);
+        assert_eq!(s.axis_minima(), (20, 20));
+        assert_eq!(s.crt_time(), Some(20));
+        for d in [Detector::Variance, Detector::Cluster, Detector::Entropy, Detector::LongestRun] {
+            assert_eq!(s.best(d).0, 20, "{d:?}");
+        }
+        assert_eq!((s.largest_cluster(20), s.longest_run(20)), (16, 4));
+        assert_eq!(s.find_picture(4), Some(20));
+        assert_eq!(s.find_picture(5), None);
+        assert_eq!(s.render(20).lines().next(), Some(format!("....####{}", ".".repeat(23)).as_str()));
+        let start = square(0);
+        assert_eq!(start.crt_time(), Some(899));
+        assert_eq!(start.best(Detector::Variance).0, 899);
+        assert_eq!(start.find_picture(4), Some(899));
+    }
+}
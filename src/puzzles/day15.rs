@@ -1,8 +1,13 @@
+pub mod replay;
+pub mod warehouse;
+
 use std::fs;
 
 use itertools::Itertools;
 use solutions::misc::{grid::Grid, point::{Point, EAST, NORTH, SOUTH, WEST}};
 
+use warehouse::{Layout, Warehouse};
+
 pub fn solve() {
     let input = fs::read_to_string("../inputs/day15").expect("Should be able to read input");
     let (grid, moves) = parse_input(&input);
@@ -26,81 +31,17 @@ fn parse_input(input:&String) -> (Grid<char>, Vec<Point>) {
 }
 
 fn part1(grid:&Grid<char>, moves:&Vec<Point>) -> usize {
-    let mut g = Grid {width: grid.width, height: grid.height, cells: grid.cells.clone()};
-    _solve(&mut g, moves)
+    _solve(grid, Layout::Single, moves)
 }
 
 fn part2(grid:&Grid<char>, moves:&Vec<Point>) -> usize {
-    let mut g = extend_grid_p2(grid);
-    _solve(&mut g, moves)
-}
-
-fn _solve(g: &mut Grid<char>, moves:&Vec<Point>) -> usize {
-    let mut r = g.find('@').expect("Should have robot");
-    for m in moves {
-        match try_move(r, *m, &g) {
-            Ok(to_move) => {
-                to_move.iter().for_each(|(_, from, _)| g[from] = '.');
-                to_move.iter().for_each(|(to, _, v)| g[to] = *v);
-                r += *m;
-            }
-            Err(_) => continue
-        }
-    }
-    score(&g)
-}
-
-fn extend_grid_p2(grid:&Grid<char>) -> Grid<char> {
-    Grid {
-        width: grid.width*2, 
-        height: grid.height, 
-        cells: grid.cells.clone().into_iter().flat_map(|c| match c {
-            '.' => ['.', '.'],
-            '@' => ['@', '.'],
-            'O' => ['[', ']'],
-            '#' => ['#', '#'],
-            _ => unreachable!()
-        }).collect_vec()
-    }
-}
-
-enum CantMoveReason {
-    OutOfBounds,
-    Wall
-}
-
-fn try_move(from: Point, dir: Point, grid:&Grid<char>) -> Result<Vec<(Point, Point, char)>,CantMoveReason> {
-    let to = from+dir;
-    let mut result = vec![(to, from, grid[from])];
-    let v = grid.try_get(to);
-    match v {
-        None => Err(CantMoveReason::OutOfBounds),
-        Some('.') => Ok(result),
-        Some('#') => Err(CantMoveReason::Wall),
-        Some(c@'[') | Some(c@']') | Some(c@'O') => {
-            match dir {
-                EAST | WEST => { result.extend(try_move(to, dir, grid)?); Ok(result) }
-                _ => {
-                    let other = match c {
-                        '[' => try_move(to+EAST, dir, grid)?,
-                        ']' => try_move(to+WEST, dir, grid)?,
-                        _ => Vec::new()
-                    };
-                    result.extend(other);
-                    result.extend(try_move(to, dir, grid)?);
-                    Ok(result)
-                } 
-            }
-        },
-        _ => unreachable!()
-    }
+    _solve(grid, Layout::Wide, moves)
 }
 
-fn score(grid:&Grid<char>) -> usize {
-    grid.points_iter()
-        .filter(|p| grid[p] == '[' || grid[p] == 'O')
-        .map(|p| (100*p.y + p.x) as usize)
-        .sum()
+fn _solve(grid:&Grid<char>, layout: Layout, moves:&[Point]) -> usize {
+    let mut w = Warehouse::from_map(grid, layout).expect("Should be a valid map");
+    w.run(moves);
+    w.gps()
 }
 
 #[cfg(test)]
@@ -0,0 +1,141 @@
+use solutions::misc::{grid::Grid, point::{Point, EAST, NORTH, SOUTH, WEST}};
+
+use super::warehouse::{Cell, Outcome, Warehouse};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub robot: Point,
+    pub origins: Vec<Point>
+}
+
+pub struct Replay {
+    walls: Grid<bool>,
+    shapes: Vec<Vec<Point>>,
+    pub moves: Vec<Point>,
+    pub outcomes: Vec<Outcome>,
+    keyframes: Vec<Frame>
+}
+
+const KEYFRAME_INTERVAL: usize = 256;
+
+fn snapshot(w: &Warehouse) -> Frame {
+    Frame { robot: w.robot, origins: w.crates.iter().map(|c| c.origin).collect() }
+}
+
+fn apply(f: &mut Frame, dir: Point, outcome: &Outcome) {
+    match outcome {
+        Outcome::Moved => f.robot += dir,
+        Outcome::Pushed(moved) => {
+            f.robot += dir;
+            moved.iter().for_each(|&i| f.origins[i] += dir);
+        }
+        Outcome::Blocked(_) => {}
+    }
+}
+
+pub fn move_glyph(dir: Point) -> char {
+    match dir { NORTH => '^', SOUTH => 'v', WEST => '<', EAST => '>', _ => '?' }
+}
+
+fn box_glyph(shape: &[Point], offset: Point) -> char {
+    match shape {
+        [_] => 'O',
+        [a, b] if *b == *a + EAST => if offset == *a { '[' } else { ']' },
+        _ => 'B'
+    }
+}
+
+impl Replay {
+    pub fn record(mut w: Warehouse, moves: &[Point]) -> Self {
+        let walls = Grid { width: w.cells.width, height: w.cells.height, cells: w.cells.cells.iter().map(|&c| c == Cell::Wall).collect() };
+        let shapes = w.crates.iter().map(|c| c.shape.clone()).collect();
+        let mut keyframes = vec![snapshot(&w)];
+        let outcomes = moves.iter().enumerate().map(|(i, &m)| {
+            let o = w.step(m);
+            if (i + 1) % KEYFRAME_INTERVAL == 0 { keyframes.push(snapshot(&w)); }
+            o
+        })
+        .collect();
+        Replay { walls, shapes, moves: moves.to_vec(), outcomes, keyframes }
+    }
+
+    pub fn len(&self) -> usize { self.moves.len() + 1 }
+
+    pub fn is_empty(&self) -> bool { false }
+
+    pub fn state(&self, n: usize) -> Option<Frame> {
+        if n > self.moves.len() { return None; }
+        let start = n / KEYFRAME_INTERVAL * KEYFRAME_INTERVAL;
+        let mut f = self.keyframes[n / KEYFRAME_INTERVAL].clone();
+        (start..n).for_each(|i| apply(&mut f, self.moves[i], &self.outcomes[i]));
+        Some(f)
+    }
+
+    pub fn frame(&self, n: usize) -> Option<Grid<char>> {
+        let f = self.state(n)?;
+        let mut g = Grid { width: self.walls.width, height: self.walls.height, cells: self.walls.cells.iter().map(|&w| if w { '#' } else { '.' }).collect() };
+        for (shape, &origin) in self.shapes.iter().zip(&f.origins) {
+            shape.iter().for_each(|&o| g[&(origin + o)] = box_glyph(shape, o));
+        }
+        g[&f.robot] = '@';
+        Some(g)
+    }
+
+    pub fn render(&self, n: usize) -> Option<String> {
+        self.frame(n).map(|g| g.to_string())
+    }
+
+    pub fn gps(&self, n: usize) -> Option<usize> {
+        self.state(n).map(|f| f.origins.iter().map(|o| (100*o.y + o.x) as usize).sum())
+    }
+
+    pub fn diff(&self, a: usize, b: usize) -> Option<Vec<(Point, char, char)>> {
+        let (fa, fb) = (self.frame(a)?, self.frame(b)?);
+        Some(fa.points_iter().filter(|p| fa[p] != fb[p]).map(|p| (p, fa[p], fb[p])).collect())
+    }
+
+    pub fn walkthrough(&self) -> String {
+        (0..self.len())
+            .map(|n| {
+                let title = if n == 0 { "Initial state".to_string() } else { format!("Move {}", move_glyph(self.moves[n - 1])) };
+                format!("{title}:\n{}\n", self.render(n).unwrap())
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::day15::{parse_input, warehouse::Layout};
+
+    #[test]
+    fn matches_published_walkthrough() {
+        let (grid, moves) = parse_input(&String::from("########\n#..O.O.#\n##@.O..#\n#...O..#\n#.#.O..#\n#...O..#\n#......#\n########\n\n<^^>>>vv<v>>v<<\n"));
+        let r = Replay::record(Warehouse::from_map(&grid, Layout::Single).unwrap(), &moves);
+        assert_eq!(r.len(), moves.len() + 1);
+        assert_eq!(r.render(0).unwrap(), grid.to_string());
+        assert_eq!(r.render(4).unwrap().lines().nth(1), Some("#..@OO.#"));
+        assert_eq!(r.render(3), r.render(2));
+        assert_eq!(r.diff(3, 4).unwrap(), [(Point::new(2, 1), '@', '.'), (Point::new(3, 1), 'O', '@'), (Point::new(4, 1), '.', 'O')]);
+        assert_eq!(r.gps(moves.len()), Some(2028));
+        assert!(r.walkthrough().starts_with("Initial state:\n########\n"));
+        assert!(r.walkthrough().contains("\nMove >:\n########\n#..@OO.#\n"));
+        assert!(r.frame(moves.len() + 1).is_none());
+    }
+
+    #[test]
+    fn replays_between_keyframes() {
+        let (grid, _) = parse_input(&String::from("#######\n#.....#\n#.O@O.#\n#.....#\n#######\n\n<\n"));
+        let moves = [WEST, EAST, EAST, WEST].repeat(KEYFRAME_INTERVAL);
+        let mut w = Warehouse::from_map(&grid, Layout::Single).unwrap();
+        let r = Replay::record(Warehouse::from_map(&grid, Layout::Single).unwrap(), &moves);
+        assert_eq!(r.keyframes.len(), 5);
+        for (n, &m) in moves.iter().enumerate() {
+            assert_eq!(r.state(n), Some(snapshot(&w)), "frame {n}");
+            w.step(m);
+        }
+        assert_eq!(r.state(moves.len()), Some(snapshot(&w)));
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+
+use solutions::misc::{grid::Grid, point::Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell { Floor, Wall, Box(usize) }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crate {
+    pub origin: Point,
+    pub shape: Vec<Point>
+}
+
+impl Crate {
+    pub fn new(origin: Point, width: usize) -> Self {
+        Crate { origin, shape: (0..width as i32).map(|x| Point::new(x, 0)).collect() }
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = Point> + '_ {
+        self.shape.iter().map(|&o| self.origin + o)
+    }
+
+    pub fn gps(&self) -> usize {
+        (100*self.origin.y + self.origin.x) as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Moved,
+    Pushed(Vec<usize>),
+    Blocked(Point)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout { Single, Wide }
+
+impl Layout {
+    pub fn scale(self) -> usize {
+        match self { Layout::Single => 1, Layout::Wide => 2 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarehouseError {
+    NoRobot,
+    UnknownTile(char),
+    Overlap(Point),
+    OutOfBounds(Point)
+}
+
+#[derive(Debug, Clone)]
+struct Record {
+    dir: Point,
+    moved: bool,
+    pushed: Vec<usize>
+}
+
+pub struct Warehouse {
+    pub cells: Grid<Cell>,
+    pub crates: Vec<Crate>,
+    pub robot: Point,
+    history: Vec<Record>
+}
+
+impl Warehouse {
+    pub fn new(walls: &Grid<bool>, robot: Point, crates: Vec<Crate>) -> Result<Self, WarehouseError> {
+        let mut cells = Grid::<Cell>::new(walls.width, walls.height, Cell::Floor);
+        walls.points_iter().filter(|p| walls[p]).for_each(|p| cells[&p] = Cell::Wall);
+        if !cells.contains(&robot) { return Err(WarehouseError::OutOfBounds(robot)); }
+        if cells[&robot] != Cell::Floor { return Err(WarehouseError::Overlap(robot)); }
+        for (id, c) in crates.iter().enumerate() {
+            for p in c.cells() {
+                match cells.try_get(p) {
+                    None => return Err(WarehouseError::OutOfBounds(p)),
+                    Some(Cell::Floor) if p != robot => cells[&p] = Cell::Box(id),
+                    Some(_) => return Err(WarehouseError::Overlap(p))
+                }
+            }
+        }
+        Ok(Warehouse { cells, crates, robot, history: Vec::new() })
+    }
+
+    pub fn from_map(map: &Grid<char>, layout: Layout) -> Result<Self, WarehouseError> {
+        let scale = layout.scale();
+        let mut walls = Grid::<bool>::new(map.width * scale, map.height, false);
+        let mut robot = None;
+        let mut crates = Vec::new();
+        for p in map.points_iter() {
+            let at = Point::new(p.x * scale as i32, p.y);
+            match map[p] {
+                '.' | ']' => {}
+                '#' => (0..scale as i32).for_each(|dx| walls[&(at + Point::new(dx, 0))] = true),
+                '@' => robot = Some(at),
+                'O' => crates.push(Crate::new(at, scale)),
+                '[' => crates.push(Crate::new(at, 2*scale)),
+                c => return Err(WarehouseError::UnknownTile(c))
+            }
+        }
+        Warehouse::new(&walls, robot.ok_or(WarehouseError::NoRobot)?, crates)
+    }
+
+    pub fn cell(&self, p: Point) -> Cell {
+        self.cells.try_get(p).copied().unwrap_or(Cell::Wall)
+    }
+
+    fn shift(&mut self, ids: &[usize], dir: Point) {
+        for &id in ids {
+            for p in self.crates[id].cells().collect::<Vec<_>>() { self.cells[&p] = Cell::Floor; }
+        }
+        for &id in ids {
+            self.crates[id].origin += dir;
+            for p in self.crates[id].cells().collect::<Vec<_>>() { self.cells[&p] = Cell::Box(id); }
+        }
+    }
+
+    pub fn step(&mut self, dir: Point) -> Outcome {
+        let mut pushed = Vec::new();
+        let mut queued = HashSet::new();
+        let mut frontier = vec![self.robot + dir];
+        while let Some(p) = frontier.pop() {
+            match self.cell(p) {
+                Cell::Floor => {}
+                Cell::Wall => {
+                    self.history.push(Record { dir, moved: false, pushed: Vec::new() });
+                    return Outcome::Blocked(p);
+                }
+                Cell::Box(id) => if queued.insert(id) {
+                    pushed.push(id);
+                    frontier.extend(self.crates[id].cells().map(|c| c + dir).filter(|&c| self.cell(c) != Cell::Box(id)));
+                }
+            }
+        }
+        self.shift(&pushed, dir);
+        self.robot += dir;
+        let outcome = if pushed.is_empty() { Outcome::Moved } else { Outcome::Pushed(pushed.clone()) };
+        self.history.push(Record { dir, moved: true, pushed });
+        outcome
+    }
+
+    pub fn run(&mut self, moves: &[Point]) -> Vec<Outcome> {
+        moves.iter().map(|&m| self.step(m)).collect()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(r) = self.history.pop() else { return false };
+        if r.moved {
+            self.shift(&r.pushed, -r.dir);
+            self.robot -= r.dir;
+        }
+        true
+    }
+
+    pub fn moves(&self) -> usize { self.history.len() }
+
+    pub fn gps(&self) -> usize {
+        self.crates.iter().map(Crate::gps).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solutions::misc::point::{EAST, NORTH, SOUTH, WEST};
+
+    #[test]
+    fn pushes_fanned_out_boxes_once_and_undoes() {
+        let map = Grid::char_grid("#######\n#.....#\n#.....#\n#.[][]#\n#..[].#\n#...@.#\n#######");
+        let mut w = Warehouse::from_map(&map, Layout::Single).unwrap();
+        let gps = w.gps();
+        assert_eq!(w.step(NORTH), Outcome::Pushed(vec![2, 1, 0]));
+        assert_eq!(w.step(NORTH), Outcome::Pushed(vec![2, 1, 0]));
+        assert_eq!(w.step(NORTH), Outcome::Blocked(Point::new(5, 0)));
+        assert_eq!(w.cell(Point::new(3, 1)), Cell::Box(0));
+        assert_eq!(w.gps(), gps - 600);
+        assert_eq!(w.moves(), 3);
+        while w.undo() {}
+        assert_eq!(w.gps(), gps);
+        assert_eq!(w.robot, Point::new(4, 5));
+        assert_eq!(w.cell(Point::new(4, 4)), Cell::Box(2));
+        assert_eq!(w.run(&[WEST, SOUTH, EAST]), [Outcome::Moved, Outcome::Blocked(Point::new(3, 6)), Outcome::Moved]);
+    }
+
+    #[test]
+    fn supports_other_shapes_and_layouts() {
+        let walls = Grid::<bool>::new(5, 4, false);
+        let l = Crate { origin: Point::new(1, 1), shape: vec![Point::new(0, 0), Point::new(0, 1), Point::new(1, 1)] };
+        let mut w = Warehouse::new(&walls, Point::new(0, 2), vec![l]).unwrap();
+        assert_eq!(w.step(EAST), Outcome::Pushed(vec![0]));
+        assert_eq!(w.step(EAST), Outcome::Pushed(vec![0]));
+        assert_eq!(w.step(EAST), Outcome::Blocked(Point::new(5, 2)));
+        assert_eq!(w.crates[0].origin, Point::new(3, 1));
+        let map = Grid::char_grid("#####\n#@O.#\n#####");
+        let wide = Warehouse::from_map(&map, Layout::Wide).unwrap();
+        assert_eq!((wide.cells.width, wide.robot), (10, Point::new(2, 1)));
+        assert_eq!(wide.crates[0].cells().collect::<Vec<_>>(), [Point::new(4, 1), Point::new(5, 1)]);
+        assert_eq!(Warehouse::from_map(&Grid::char_grid("#.#"), Layout::Single).err(), Some(WarehouseError::NoRobot));
+    }
+}
@@ -1,7 +1,7 @@
-use std::{cmp::Ordering, collections::{HashMap, HashSet}, fs};
+use std::{cmp::Ordering, fs};
 
 use itertools::Itertools;
-use solutions::misc::{graph::{Edge, Graph}, grid::Grid, point::{Point, EAST, ORTHO_DIR}};
+use solutions::misc::{graph::search, grid::Grid, point::{Point, EAST}};
 
 pub fn solve() {
     let input = fs::read_to_string("../inputs/day16").expect("Should be able to read input");
@@ -15,35 +15,19 @@ fn parse_input(input:&String) -> Grid<char> {
 }
 
 fn _solve(grid:&Grid<char>) -> (usize, usize) {
-    let g = Graph::from_grid(grid);
     let start = Vertex::new(grid.find('S').unwrap(), EAST);
     let e = grid.find('E').unwrap();
-    let ends = ORTHO_DIR.map(|d| Vertex::new(e, d)).to_vec();
-    let visits = g.dijkstra(start);
-    let shortest = ends.iter()
-        .filter_map(|v| visits[v].as_ref().map(|p| p.0).or(Some(usize::MAX)))
-        .min()
-        .unwrap();
-    let seats = ends.iter()
-        .filter_map(|v| visits[v].as_ref().filter(|d| d.0 == shortest).and(Some(v)))
-        .flat_map(|e| mark_path(start, *e, &visits))
+    let visits = search::dijkstra(start, |v| moves(v, grid), |v| v.pos == e);
+    let shortest = visits.goal_cost().unwrap();
+    let seats = visits.path_dag()
+        .vertices_on_paths(&visits.goals)
+        .iter()
+        .map(|v| v.pos)
+        .unique()
         .count();
     (shortest, seats)
 }
 
-fn mark_path(start:Vertex, e:Vertex, distances: &HashMap<&Vertex, Option<(usize, Vec<Vertex>)>>) -> HashSet<Point> {
-    let mut cur = vec![e];
-    let mut r = HashSet::new();
-    while !r.contains(&start) {
-        let next = cur.iter()
-            .flat_map(|v| distances[v].as_ref().unwrap().1.clone())
-            .collect_vec();
-        r.extend(cur);
-        cur = next;
-    }
-    r.iter().map(|v| v.pos).collect()
-}
-
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
 struct Vertex {
     pos: Point,
@@ -66,34 +50,14 @@ impl Vertex {
     }
 }
 
-trait FromGrid {
-    fn from_grid(grid:&Grid<char>) -> Self;
-}
-
-impl FromGrid for Graph<Vertex, usize> {
-    fn from_grid(grid:&Grid<char>) -> Self {
-        let vertices = grid.points_iter()
-            .filter(|&p| grid[p] != '#')
-            .flat_map(|p| ORTHO_DIR.map(|d| Vertex::new(p,d)))
-            .collect_vec();
-        let mut edges = HashMap::new();
-        let mut costs = HashMap::new();
-        for v in vertices.clone() {
-            let es = get_edges(&v);
-            let entry = edges.entry(v).or_insert(Vec::new());
-            for (e,c) in es {
-                costs.insert(e.clone(), c);
-                entry.push(e);
-            }
-        }
-        Graph { vertices, edges, costs }
-    }
+fn moves<'a>(v:&Vertex, grid:&'a Grid<char>) -> impl Iterator<Item = (Vertex, usize)> + 'a {
+    get_edges(v).into_iter().filter(|(to,_)| grid[to.pos] != '#')
 }
 
-fn get_edges(v:&Vertex) -> [(Edge<Vertex>, usize);3] {[
-    (Edge::new(v.clone(), Vertex::new(v.pos, v.dir.rotate_cw())), 1000),
-    (Edge::new(v.clone(), Vertex::new(v.pos, v.dir.rotate_ccw())), 1000),
-    (Edge::new(v.clone(), Vertex::new(v.pos + v.dir, v.dir)), 1)
+fn get_edges(v:&Vertex) -> [(Vertex, usize);3] {[
+    (Vertex::new(v.pos, v.dir.rotate_cw()), 1000),
+    (Vertex::new(v.pos, v.dir.rotate_ccw()), 1000),
+    (Vertex::new(v.pos + v.dir, v.dir), 1)
 ]}
 
 #[cfg(test)]
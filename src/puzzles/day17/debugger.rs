@@ -0,0 +1,160 @@
+use std::{collections::HashSet, io::{self, BufRead, Write}};
+
+use super::vm::{disassemble, format_output, Register, Step, Vm, VmError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Stepped,
+    Breakpoint(usize),
+    Watchpoint { reg: Register, old: u64, new: u64 },
+    Output(u8),
+    Halted
+}
+
+pub struct Debugger {
+    pub vm: Vm,
+    breakpoints: HashSet<usize>,
+    watchpoints: HashSet<Register>
+}
+
+fn parse_register(s: &str) -> Option<Register> {
+    match s.to_ascii_uppercase().as_str() {
+        "A" => Some(Register::A),
+        "B" => Some(Register::B),
+        "C" => Some(Register::C),
+        _ => None
+    }
+}
+
+impl Debugger {
+    pub fn new(vm: Vm) -> Self {
+        Debugger { vm, breakpoints: HashSet::new(), watchpoints: HashSet::new() }
+    }
+
+    pub fn add_breakpoint(&mut self, ip: usize) { self.breakpoints.insert(ip); }
+
+    pub fn remove_breakpoint(&mut self, ip: usize) { self.breakpoints.remove(&ip); }
+
+    pub fn watch(&mut self, reg: Register) { self.watchpoints.insert(reg); }
+
+    pub fn unwatch(&mut self, reg: Register) { self.watchpoints.remove(&reg); }
+
+    pub fn step(&mut self) -> Result<Stop, VmError> {
+        let before = self.vm.regs;
+        let step = self.vm.step()?;
+        let changed = [Register::A, Register::B, Register::C].into_iter()
+            .filter(|r| self.watchpoints.contains(r))
+            .find(|&r| before.get(r) != self.vm.regs.get(r));
+        Ok(match (step, changed) {
+            (Step::Halted, _) => Stop::Halted,
+            (_, Some(reg)) => Stop::Watchpoint { reg, old: before.get(reg), new: self.vm.regs.get(reg) },
+            (Step::Output(v), _) => Stop::Output(v),
+            (Step::Continue, _) => Stop::Stepped
+        })
+    }
+
+    fn resume(&mut self, stop_on_output: bool) -> Result<Stop, VmError> {
+        loop {
+            match self.step()? {
+                Stop::Stepped => {}
+                Stop::Output(_) if !stop_on_output => {}
+                stop => return Ok(stop)
+            }
+            if self.vm.halted() { return Ok(Stop::Halted); }
+            if self.breakpoints.contains(&self.vm.ip) { return Ok(Stop::Breakpoint(self.vm.ip)); }
+        }
+    }
+
+    pub fn cont(&mut self) -> Result<Stop, VmError> { self.resume(false) }
+
+    pub fn run_until_output(&mut self) -> Result<Stop, VmError> { self.resume(true) }
+
+    pub fn execute(&mut self, command: &str) -> Result<String, VmError> {
+        let err = || VmError::Command { line: None, text: command.to_string() };
+        let words = command.split_whitespace().collect::<Vec<_>>();
+        let stop = match words.as_slice() {
+            ["break" | "b", ip] => { self.add_breakpoint(ip.parse().map_err(|_| err())?); return Ok(format!("breakpoint at {ip}")); }
+            ["delete" | "d", ip] => { self.remove_breakpoint(ip.parse().map_err(|_| err())?); return Ok(format!("deleted {ip}")); }
+            ["watch" | "w", r] => { self.watch(parse_register(r).ok_or_else(err)?); return Ok(format!("watching {r}")); }
+            ["unwatch", r] => { self.unwatch(parse_register(r).ok_or_else(err)?); return Ok(format!("unwatched {r}")); }
+            ["regs" | "r"] => return Ok(self.state()),
+            ["output" | "o"] => return Ok(format_output(&self.vm.output)),
+            ["disasm"] => return disassemble(&self.vm.program),
+            ["step" | "s"] => self.step()?,
+            ["continue" | "c"] => self.cont()?,
+            ["next-output" | "n"] => self.run_until_output()?,
+            _ => return Err(err())
+        };
+        Ok(format!("{stop:?}; {}", self.state()))
+    }
+
+    fn state(&self) -> String {
+        let r = &self.vm.regs;
+        let next = self.vm.current().ok().flatten().map(|i| i.to_string()).unwrap_or_else(|| "-".to_string());
+        format!("IP={} A={} B={} C={} next: {next}", self.vm.ip, r.a, r.b, r.c)
+    }
+
+    pub fn run_script(&mut self, script: &str) -> Result<Vec<String>, VmError> {
+        script.lines().enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(n, l)| self.execute(l.trim()).map_err(|e| match e {
+                VmError::Command { text, .. } => VmError::Command { line: Some(n + 1), text },
+                e => e
+            }))
+            .collect()
+    }
+
+    pub fn repl(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        write!(output, "(dbg) ")?;
+        output.flush()?;
+        for line in input.lines() {
+            let line = line?;
+            if matches!(line.trim(), "quit" | "q") { break; }
+            match self.execute(line.trim()) {
+                Ok(s) => writeln!(output, "{s}")?,
+                Err(e) => writeln!(output, "error: {e:?}")?
+            }
+            write!(output, "(dbg) ")?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::day17::vm::Registers;
+
+    fn debugger() -> Debugger {
+        Debugger::new(Vm::new(vec![0,1,5,4,3,0], Registers::new(729, 0, 0)))
+    }
+
+    #[test]
+    fn breakpoints_and_watchpoints() {
+        let mut d = debugger();
+        assert_eq!(d.step().unwrap(), Stop::Stepped);
+        assert_eq!(d.run_until_output().unwrap(), Stop::Output(4));
+        d.add_breakpoint(2);
+        assert_eq!(d.cont().unwrap(), Stop::Breakpoint(2));
+        assert_eq!(d.vm.output, vec![4]);
+        d.remove_breakpoint(2);
+        d.watch(Register::A);
+        assert_eq!(d.cont().unwrap(), Stop::Watchpoint { reg: Register::A, old: 182, new: 91 });
+        d.unwatch(Register::A);
+        assert_eq!(d.cont().unwrap(), Stop::Halted);
+        assert_eq!(format_output(&d.vm.output), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn scripted_session() {
+        let mut d = debugger();
+        let out = d.run_script("break 4\ncontinue\nregs\nn\noutput").unwrap();
+        assert_eq!(out[1], "Breakpoint(4); IP=4 A=364 B=0 C=0 next: jnz 0");
+        assert_eq!(out[4], "4,6");
+        assert!(matches!(d.run_script("step\nfly"), Err(VmError::Command { line: Some(2), .. })));
+        let mut buf = Vec::new();
+        debugger().repl("s\nq\n".as_bytes(), &mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "(dbg) Stepped; IP=2 A=364 B=0 C=0 next: out A\n(dbg) ");
+    }
+}
@@ -0,0 +1,121 @@
+use super::{symbolic::{self, SymbolicError}, vm::{Instruction, Opcode, Operand, Register, Registers, Vm, VmError}};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuineError {
+    Vm(VmError),
+    NotALoop,
+    ExtraJump { ip: usize },
+    ShiftA { found: usize },
+    Outputs { found: usize },
+    CarriedState { ip: usize, reg: Register },
+    TooLong,
+    NoSolution
+}
+
+impl From<VmError> for QuineError {
+    fn from(e: VmError) -> Self { QuineError::Vm(e) }
+}
+
+fn decode(program: &[u8]) -> Result<Vec<Instruction>, VmError> {
+    if program.len() % 2 == 1 { return Err(VmError::MissingOperand { ip: program.len() - 1 }); }
+    program.chunks(2).enumerate()
+        .map(|(i, c)| Instruction::decode(c[0], c[1], 2*i))
+        .collect()
+}
+
+fn reads(ins: &Instruction) -> Vec<Register> {
+    let combo = |o: Operand| match o {
+        Operand::B => vec![Register::B],
+        Operand::C => vec![Register::C],
+        _ => vec![]
+    };
+    match ins.op {
+        Opcode::Bxl => vec![Register::B],
+        Opcode::Bxc => vec![Register::B, Register::C],
+        op if op.takes_combo() => combo(ins.combo()),
+        _ => vec![]
+    }
+}
+
+pub fn check_structure(program: &[u8]) -> Result<(), QuineError> {
+    let code = decode(program)?;
+    let (last, body) = code.split_last().ok_or(QuineError::NotALoop)?;
+    if last.op != Opcode::Jnz || last.arg != 0 { return Err(QuineError::NotALoop); }
+    if let Some(i) = body.iter().position(|ins| ins.op == Opcode::Jnz) { return Err(QuineError::ExtraJump { ip: 2*i }); }
+    let shifts = body.iter().filter(|ins| ins.op == Opcode::Adv).collect::<Vec<_>>();
+    if shifts.len() != 1 || shifts[0].combo() != Operand::Literal(3) { return Err(QuineError::ShiftA { found: shifts.len() }); }
+    let outputs = body.iter().filter(|ins| ins.op == Opcode::Out).count();
+    if outputs != 1 { return Err(QuineError::Outputs { found: outputs }); }
+    let mut written = Vec::new();
+    for (i, ins) in body.iter().enumerate() {
+        if let Some(&reg) = reads(ins).iter().find(|r| !written.contains(*r)) {
+            return Err(QuineError::CarriedState { ip: 2*i, reg });
+        }
+        match ins.op {
+            Opcode::Bxl | Opcode::Bst | Opcode::Bxc | Opcode::Bdv => written.push(Register::B),
+            Opcode::Cdv => written.push(Register::C),
+            _ => {}
+        }
+    }
+    if program.len() * 3 > 64 { return Err(QuineError::TooLong); }
+    Ok(())
+}
+
+fn output(program: &[u8], a: u64) -> Result<Vec<u8>, VmError> {
+    let mut vm = Vm::new(program.to_vec(), Registers::new(a, 0, 0)).with_step_limit(program.len() * program.len());
+    vm.run()?;
+    Ok(vm.output)
+}
+
+fn search(program: &[u8], matched: usize, prefix: u64, fits: &impl Fn(u64, &[u8]) -> Result<bool, VmError>) -> Result<Option<u64>, VmError> {
+    if matched == program.len() { return Ok(Some(prefix)); }
+    let suffix = &program[program.len() - matched - 1..];
+    for a in (0..8).map(|d| (prefix << 3) + d).filter(|&a| a != 0) {
+        if !fits(a, suffix)? { continue; }
+        if let Some(found) = search(program, matched + 1, a, fits)? { return Ok(Some(found)); }
+    }
+    Ok(None)
+}
+
+// Each iteration consumes the lowest 3 bits of A, so A is built from the most significant
+// digit down, keeping only candidates whose output matches that suffix of the program.
+pub fn find_quine(program: &[u8]) -> Result<u64, QuineError> {
+    check_structure(program)?;
+    search(program, 0, 0, &|a, suffix| Ok(output(program, a)? == suffix))?.ok_or(QuineError::NoSolution)
+}
+
+pub fn find_quine_symbolic(program: &[u8]) -> Result<u64, QuineError> {
+    check_structure(program)?;
+    let summary = symbolic::summarize(program).map_err(|e| match e {
+        SymbolicError::Vm(e) => QuineError::Vm(e),
+        SymbolicError::NotALoop => QuineError::NotALoop,
+        SymbolicError::Jump { ip } => QuineError::ExtraJump { ip }
+    })?;
+    let out = &summary.outputs[0];
+    search(program, 0, 0, &|a, suffix| Ok(out.eval(&Registers::new(a, 0, 0)) == suffix[0] as u64))?.ok_or(QuineError::NoSolution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_unsupported_shapes() {
+        assert_eq!(find_quine(&[5,4,0,3]), Err(QuineError::NotALoop));
+        assert_eq!(find_quine(&[0,3,3,2,5,4,3,0]), Err(QuineError::ExtraJump { ip: 2 }));
+        assert_eq!(find_quine(&[0,1,5,4,3,0]), Err(QuineError::ShiftA { found: 1 }));
+        assert_eq!(find_quine(&[0,3,5,4,5,4,3,0]), Err(QuineError::Outputs { found: 2 }));
+        assert_eq!(find_quine(&[0,3,5,5,3,0]), Err(QuineError::CarriedState { ip: 2, reg: Register::B }));
+        assert_eq!(find_quine(&[0,3,1,2,5,5,3,0]), Err(QuineError::CarriedState { ip: 2, reg: Register::B }));
+        assert_eq!(find_quine(&[0,3,5,1,3,0]), Err(QuineError::NoSolution));
+    }
+
+    #[test]
+    fn finds_quine_with_scrambled_digits() {
+        let p = [2,4,1,2,7,5,4,7,1,3,5,5,0,3,3,0];
+        let a = find_quine(&p).unwrap();
+        assert_eq!(output(&p, a).unwrap(), p);
+        assert_ne!(output(&p, a - 1).unwrap(), p);
+        assert_eq!(find_quine_symbolic(&p), Ok(a));
+    }
+}
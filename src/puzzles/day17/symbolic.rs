@@ -0,0 +1,192 @@
+use std::fmt;
+
+use super::vm::{Instruction, Opcode, Operand, Register, Registers, VmError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Expr {
+    Const(u64),
+    Reg(Register),
+    Xor(Box<Expr>, Box<Expr>),
+    Shr(Box<Expr>, Box<Expr>),
+    Mod8(Box<Expr>)
+}
+
+impl Expr {
+    fn terms(self, into: &mut Vec<Expr>) {
+        match self {
+            Expr::Xor(a, b) => { a.terms(into); b.terms(into); }
+            e => into.push(e)
+        }
+    }
+
+    pub fn xor(a: Expr, b: Expr) -> Expr {
+        let mut terms = Vec::new();
+        a.terms(&mut terms);
+        b.terms(&mut terms);
+        let constant = terms.iter().fold(0, |acc, t| if let Expr::Const(c) = t { acc ^ c } else { acc });
+        terms.retain(|t| !matches!(t, Expr::Const(_)));
+        terms.sort();
+        let mut kept: Vec<Expr> = Vec::new();
+        for t in terms {
+            if kept.last() == Some(&t) { kept.pop(); } else { kept.push(t); }
+        }
+        if constant != 0 || kept.is_empty() { kept.push(Expr::Const(constant)); }
+        kept.into_iter().reduce(|a, b| Expr::Xor(Box::new(a), Box::new(b))).unwrap()
+    }
+
+    pub fn shift_right(x: Expr, n: Expr) -> Expr {
+        match (x, n) {
+            (Expr::Const(x), Expr::Const(n)) => Expr::Const(shift(x, n)),
+            (Expr::Const(0), _) => Expr::Const(0),
+            (x, Expr::Const(0)) => x,
+            (Expr::Shr(x, inner), Expr::Const(n)) if matches!(*inner, Expr::Const(_)) => {
+                let Expr::Const(m) = *inner else { unreachable!() };
+                Expr::shift_right(*x, Expr::Const(m + n))
+            }
+            (x, n) => Expr::Shr(Box::new(x), Box::new(n))
+        }
+    }
+
+    // Bitwise, so it distributes over xor and keeps expressions small.
+    pub fn mod8(x: Expr) -> Expr {
+        match x {
+            Expr::Const(c) => Expr::Const(c % 8),
+            Expr::Mod8(_) => x,
+            Expr::Xor(a, b) => Expr::xor(Expr::mod8(*a), Expr::mod8(*b)),
+            x => Expr::Mod8(Box::new(x))
+        }
+    }
+
+    pub fn eval(&self, r: &Registers) -> u64 {
+        match self {
+            Expr::Const(c) => *c,
+            Expr::Reg(reg) => r.get(*reg),
+            Expr::Xor(a, b) => a.eval(r) ^ b.eval(r),
+            Expr::Shr(x, n) => shift(x.eval(r), n.eval(r)),
+            Expr::Mod8(x) => x.eval(r) % 8
+        }
+    }
+
+    pub fn depends_on(&self, reg: Register) -> bool {
+        match self {
+            Expr::Const(_) => false,
+            Expr::Reg(r) => *r == reg,
+            Expr::Xor(a, b) | Expr::Shr(a, b) => a.depends_on(reg) || b.depends_on(reg),
+            Expr::Mod8(x) => x.depends_on(reg)
+        }
+    }
+
+    fn fmt_nested(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Const(_) | Expr::Reg(_) => write!(f, "{self}"),
+            _ => write!(f, "({self})")
+        }
+    }
+}
+
+fn shift(x: u64, n: u64) -> u64 {
+    u32::try_from(n).ok().and_then(|n| x.checked_shr(n)).unwrap_or(0)
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Const(c) => write!(f, "{c}"),
+            Expr::Reg(Register::A) => write!(f, "a"),
+            Expr::Reg(Register::B) => write!(f, "b"),
+            Expr::Reg(Register::C) => write!(f, "c"),
+            Expr::Xor(a, b) => {
+                // xor is associative, so chains print flat
+                if matches!(**a, Expr::Xor(..)) { write!(f, "{a}")?; } else { a.fmt_nested(f)?; }
+                write!(f, " ^ ")?;
+                b.fmt_nested(f)
+            }
+            Expr::Shr(x, n) => { x.fmt_nested(f)?; write!(f, " >> ")?; n.fmt_nested(f) }
+            Expr::Mod8(x) => { x.fmt_nested(f)?; write!(f, " % 8") }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolicError {
+    Vm(VmError),
+    NotALoop,
+    Jump { ip: usize }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopSummary {
+    pub outputs: Vec<Expr>,
+    pub a: Expr,
+    pub b: Expr,
+    pub c: Expr
+}
+
+impl LoopSummary {
+    // Registers B and C are recomputed from A every iteration, so outputs only depend on A.
+    pub fn depends_only_on_a(&self) -> bool {
+        self.outputs.iter().chain([&self.a])
+            .all(|e| !e.depends_on(Register::B) && !e.depends_on(Register::C))
+    }
+}
+
+impl fmt::Display for LoopSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for o in &self.outputs { writeln!(f, "out {o}")?; }
+        writeln!(f, "a = {}", self.a)?;
+        writeln!(f, "b = {}", self.b)?;
+        write!(f, "c = {}", self.c)
+    }
+}
+
+pub fn summarize(program: &[u8]) -> Result<LoopSummary, SymbolicError> {
+    if program.len() % 2 == 1 { return Err(SymbolicError::Vm(VmError::MissingOperand { ip: program.len() - 1 })); }
+    let code = program.chunks(2).enumerate()
+        .map(|(i, c)| Instruction::decode(c[0], c[1], 2*i))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(SymbolicError::Vm)?;
+    let (last, body) = code.split_last().ok_or(SymbolicError::NotALoop)?;
+    if last.op != Opcode::Jnz || last.arg != 0 { return Err(SymbolicError::NotALoop); }
+    let mut s = LoopSummary { outputs: Vec::new(), a: Expr::Reg(Register::A), b: Expr::Reg(Register::B), c: Expr::Reg(Register::C) };
+    for (i, ins) in body.iter().enumerate() {
+        let combo = |s: &LoopSummary| match ins.combo() {
+            Operand::Literal(v) => Expr::Const(v as u64),
+            Operand::A => s.a.clone(),
+            Operand::B => s.b.clone(),
+            Operand::C => s.c.clone()
+        };
+        match ins.op {
+            Opcode::Adv => s.a = Expr::shift_right(s.a.clone(), combo(&s)),
+            Opcode::Bxl => s.b = Expr::xor(s.b.clone(), Expr::Const(ins.arg as u64)),
+            Opcode::Bst => s.b = Expr::mod8(combo(&s)),
+            Opcode::Jnz => return Err(SymbolicError::Jump { ip: 2*i }),
+            Opcode::Bxc => s.b = Expr::xor(s.b.clone(), s.c.clone()),
+            Opcode::Out => s.outputs.push(Expr::mod8(combo(&s))),
+            Opcode::Bdv => s.b = Expr::shift_right(s.a.clone(), combo(&s)),
+            Opcode::Cdv => s.c = Expr::shift_right(s.a.clone(), combo(&s)),
+        }
+    }
+    Ok(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::day17::vm;
+
+    #[test]
+    fn recovers_output_formula() {
+        let p = [2,4,1,2,7,5,4,7,1,3,5,5,0,3,3,0];
+        let s = summarize(&p).unwrap();
+        assert_eq!(s.outputs.len(), 1);
+        assert_eq!(s.outputs[0].to_string(), "(a % 8) ^ ((a >> ((a % 8) ^ 2)) % 8) ^ 1");
+        assert_eq!(s.a.to_string(), "a >> 3");
+        assert!(s.depends_only_on_a());
+        for a in [1, 7, 8, 12345, 1 << 40] {
+            let first = vm::run(&p, Registers::new(a, 0, 0)).unwrap()[0];
+            assert_eq!(s.outputs[0].eval(&Registers::new(a, 0, 0)), first as u64);
+        }
+        assert!(!summarize(&[0,3,5,5,3,0]).unwrap().depends_only_on_a());
+        assert_eq!(summarize(&[0,3,3,0,3,0]), Err(SymbolicError::Jump { ip: 2 }));
+    }
+}
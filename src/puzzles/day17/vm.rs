@@ -0,0 +1,262 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode { Adv, Bxl, Bst, Jnz, Bxc, Out, Bdv, Cdv }
+
+const OPCODES: [Opcode; 8] = [Opcode::Adv, Opcode::Bxl, Opcode::Bst, Opcode::Jnz, Opcode::Bxc, Opcode::Out, Opcode::Bdv, Opcode::Cdv];
+
+impl Opcode {
+    pub fn from_u8(v: u8) -> Option<Self> { OPCODES.get(v as usize).copied() }
+
+    pub fn code(self) -> u8 { self as u8 }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv", Opcode::Bxl => "bxl", Opcode::Bst => "bst", Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc", Opcode::Out => "out", Opcode::Bdv => "bdv", Opcode::Cdv => "cdv",
+        }
+    }
+
+    pub fn takes_combo(self) -> bool {
+        matches!(self, Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand { Literal(u8), A, B, C }
+
+impl Operand {
+    pub fn combo(v: u8) -> Option<Self> {
+        match v {
+            0..=3 => Some(Operand::Literal(v)),
+            4 => Some(Operand::A),
+            5 => Some(Operand::B),
+            6 => Some(Operand::C),
+            _ => None
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        match self { Operand::Literal(v) => v, Operand::A => 4, Operand::B => 5, Operand::C => 6 }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Literal(v) => write!(f, "{v}"),
+            Operand::A => write!(f, "A"),
+            Operand::B => write!(f, "B"),
+            Operand::C => write!(f, "C"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub op: Opcode,
+    pub arg: u8
+}
+
+impl Instruction {
+    pub fn decode(op: u8, arg: u8, ip: usize) -> Result<Self, VmError> {
+        let op = Opcode::from_u8(op).ok_or(VmError::InvalidOpcode { ip, value: op })?;
+        if arg > 7 { return Err(VmError::InvalidOperand { ip, value: arg }); }
+        if op.takes_combo() && arg == 7 { return Err(VmError::ReservedOperand { ip }); }
+        Ok(Instruction { op, arg })
+    }
+
+    pub fn combo(&self) -> Operand {
+        Operand::combo(self.arg).expect("combo operands are validated on decode")
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.op {
+            Opcode::Bxc if self.arg == 0 => write!(f, "bxc"),
+            op if op.takes_combo() => write!(f, "{} {}", op.mnemonic(), self.combo()),
+            op => write!(f, "{} {}", op.mnemonic(), self.arg),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VmError {
+    InvalidOpcode { ip: usize, value: u8 },
+    InvalidOperand { ip: usize, value: u8 },
+    ReservedOperand { ip: usize },
+    MissingOperand { ip: usize },
+    StepLimit(usize),
+    Syntax { line: usize, text: String },
+    Command { line: Option<usize>, text: String }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Register { A, B, C }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Registers {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64
+}
+
+impl Registers {
+    pub fn new(a: u64, b: u64, c: u64) -> Self { Registers { a, b, c } }
+
+    pub fn get(&self, r: Register) -> u64 {
+        match r { Register::A => self.a, Register::B => self.b, Register::C => self.c }
+    }
+
+    pub fn read(&self, o: Operand) -> u64 {
+        match o { Operand::Literal(v) => v as u64, Operand::A => self.a, Operand::B => self.b, Operand::C => self.c }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step { Continue, Output(u8), Halted }
+
+#[derive(Debug, Clone)]
+pub struct Vm {
+    pub program: Vec<u8>,
+    pub regs: Registers,
+    pub ip: usize,
+    pub output: Vec<u8>,
+    pub steps: usize,
+    pub step_limit: Option<usize>
+}
+
+fn divide(a: u64, shift: u64) -> u64 {
+    u32::try_from(shift).ok().and_then(|s| a.checked_shr(s)).unwrap_or(0)
+}
+
+impl Vm {
+    pub fn new(program: Vec<u8>, regs: Registers) -> Self {
+        Vm { program, regs, ip: 0, output: Vec::new(), steps: 0, step_limit: None }
+    }
+
+    pub fn with_step_limit(mut self, limit: usize) -> Self {
+        self.step_limit = Some(limit); self
+    }
+
+    pub fn halted(&self) -> bool { self.ip >= self.program.len() }
+
+    pub fn current(&self) -> Result<Option<Instruction>, VmError> {
+        if self.halted() { return Ok(None); }
+        let arg = *self.program.get(self.ip + 1).ok_or(VmError::MissingOperand { ip: self.ip })?;
+        Instruction::decode(self.program[self.ip], arg, self.ip).map(Some)
+    }
+
+    pub fn step(&mut self) -> Result<Step, VmError> {
+        self.step_with(|_, _, _| {})
+    }
+
+    pub fn step_with(&mut self, mut observer: impl FnMut(usize, &Registers, &Instruction)) -> Result<Step, VmError> {
+        let Some(ins) = self.current()? else { return Ok(Step::Halted) };
+        if self.step_limit.is_some_and(|l| self.steps >= l) { return Err(VmError::StepLimit(self.steps)); }
+        observer(self.ip, &self.regs, &ins);
+        self.steps += 1;
+        self.ip += 2;
+        let r = &mut self.regs;
+        match ins.op {
+            Opcode::Adv => r.a = divide(r.a, r.read(ins.combo())),
+            Opcode::Bxl => r.b ^= ins.arg as u64,
+            Opcode::Bst => r.b = r.read(ins.combo()) % 8,
+            Opcode::Jnz => if r.a != 0 { self.ip = ins.arg as usize },
+            Opcode::Bxc => r.b ^= r.c,
+            Opcode::Out => {
+                let v = (r.read(ins.combo()) % 8) as u8;
+                self.output.push(v);
+                return Ok(Step::Output(v));
+            }
+            Opcode::Bdv => r.b = divide(r.a, r.read(ins.combo())),
+            Opcode::Cdv => r.c = divide(r.a, r.read(ins.combo())),
+        }
+        Ok(Step::Continue)
+    }
+
+    pub fn run(&mut self) -> Result<&[u8], VmError> {
+        self.run_with(|_, _, _| {})
+    }
+
+    pub fn run_with(&mut self, mut observer: impl FnMut(usize, &Registers, &Instruction)) -> Result<&[u8], VmError> {
+        while self.step_with(&mut observer)? != Step::Halted {}
+        Ok(&self.output)
+    }
+}
+
+pub fn print_trace(ip: usize, r: &Registers, ins: &Instruction) {
+    println!("IP={ip:2} A={a:16} B={b:16} C={c:16}  {ins}", a = r.a, b = r.b, c = r.c);
+}
+
+pub fn run(program: &[u8], regs: Registers) -> Result<Vec<u8>, VmError> {
+    let mut vm = Vm::new(program.to_vec(), regs);
+    vm.run()?;
+    Ok(vm.output)
+}
+
+pub fn disassemble(program: &[u8]) -> Result<String, VmError> {
+    program.chunks(2).enumerate()
+        .map(|(i, c)| match c {
+            [op, arg] => Instruction::decode(*op, *arg, 2*i).map(|ins| ins.to_string()),
+            _ => Err(VmError::MissingOperand { ip: 2*i })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|lines| lines.join("\n"))
+}
+
+pub fn assemble(src: &str) -> Result<Vec<u8>, VmError> {
+    let mut program = Vec::new();
+    for (n, line) in src.lines().enumerate() {
+        let text = line.split(';').next().unwrap_or("").trim();
+        if text.is_empty() { continue; }
+        let err = || VmError::Syntax { line: n + 1, text: line.to_string() };
+        let (mnemonic, arg) = text.split_once(char::is_whitespace).map(|(m, a)| (m, a.trim())).unwrap_or((text, ""));
+        let op = OPCODES.iter().find(|o| o.mnemonic().eq_ignore_ascii_case(mnemonic)).ok_or_else(err)?;
+        let arg = match (op, arg.to_ascii_uppercase().as_str()) {
+            (Opcode::Bxc, "") => 0,
+            (op, "A" | "B" | "C") if op.takes_combo() => 4 + arg.to_ascii_uppercase().as_bytes()[0] - b'A',
+            (op, v) => v.parse::<u8>().ok()
+                .filter(|&v| if op.takes_combo() { v < 4 } else { v < 8 })
+                .ok_or_else(err)?,
+        };
+        program.extend([op.code(), arg]);
+    }
+    Ok(program)
+}
+
+pub fn format_output(out: &[u8]) -> String {
+    out.iter().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_and_errors() {
+        let p = vec![2,4,1,2,7,5,4,7,1,3,5,5,0,3,3,0];
+        let text = disassemble(&p).unwrap();
+        assert_eq!(text.lines().take(4).join("; "), "bst A; bxl 2; cdv B; bxc 7");
+        assert_eq!(assemble(&text).unwrap(), p);
+        assert_eq!(assemble("out C\nbxc ; comment\njnz 0").unwrap(), vec![5,6,4,0,3,0]);
+        assert!(matches!(assemble("bst 5"), Err(VmError::Syntax { line: 1, .. })));
+        assert_eq!(run(&[8,0], Registers::default()), Err(VmError::InvalidOpcode { ip: 0, value: 8 }));
+        assert_eq!(run(&[5,7], Registers::default()), Err(VmError::ReservedOperand { ip: 0 }));
+        assert_eq!(run(&[1,1,0], Registers::default()), Err(VmError::MissingOperand { ip: 2 }));
+        let mut vm = Vm::new(vec![3,0], Registers::new(1, 0, 0)).with_step_limit(100);
+        assert_eq!(vm.run(), Err(VmError::StepLimit(100)));
+    }
+
+    #[test]
+    fn observer_sees_every_instruction() {
+        let mut seen = Vec::new();
+        let mut vm = Vm::new(vec![0,1,5,4,3,0], Registers::new(8, 0, 0));
+        vm.run_with(|ip, r, ins| seen.push((ip, r.a, ins.op))).unwrap();
+        assert_eq!(seen.len(), 12);
+        assert_eq!(seen[..4], [(0, 8, Opcode::Adv), (2, 4, Opcode::Out), (4, 4, Opcode::Jnz), (0, 4, Opcode::Adv)]);
+    }
+}
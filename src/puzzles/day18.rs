@@ -1,7 +1,7 @@
-use std::{collections::{HashMap, HashSet}, fs, iter, usize};
+use std::{fs, iter, usize};
 
 use itertools::Itertools;
-use solutions::misc::{graph::{Edge, Graph}, point::Point};
+use solutions::misc::{graph::search, grid::Grid, point::Point};
 
 #[derive(Clone, Debug, PartialEq)]
 enum Errors {
@@ -48,27 +48,16 @@ fn part2(obstacles: &Vec<Point>, grid_size:usize) -> Point {
 }
 
 fn shortest_path(obstacles: &[Point], grid_size:usize) -> Result<usize, Errors> {
-    let grid_size = grid_size as i32;
     let start = Point::new(0,0);
-    let end = Point::new(grid_size-1,grid_size-1);
-    let o = obstacles.iter().collect::<HashSet<_>>();
-    let vs = (0..grid_size).cartesian_product(0..grid_size).map(Point::from_tuple).filter(|p| !o.contains(p)).collect_vec();
-    let es = vs.iter().map(|p| (p.clone(), edges(p, &o, grid_size))).collect::<HashMap<_,_>>();
-    let cs = es.iter().flat_map(|x| x.1.iter().map(|e| (e.clone(),1))).collect();
-    let g = Graph {vertices: vs, edges: es, costs: cs};
-    g.dijkstra(start) [&end]
-        .as_ref()
-        .map(|x| x.0)
+    let end = Point::new(grid_size as i32-1,grid_size as i32-1);
+    let mut grid = Grid::<bool>::new(grid_size, grid_size, true);
+    obstacles.iter().for_each(|o| grid[o] = false);
+    let open = grid.value_checker(true);
+    search::bfs(start, |p| p.ortho_neighbors().into_iter().filter(&open), |p| *p == end)
+        .goal_cost()
         .ok_or(Errors::NoPath)
 }
 
-fn edges(p:&Point, obstacles: &HashSet<&Point>, grid_size:i32) -> Vec<Edge<Point>> {
-    p.ortho_neighbors().iter()
-        .filter(|pt| !obstacles.contains(pt) && pt.x >=0 && pt.y >= 0 && pt.x < grid_size && pt.y < grid_size)
-        .map(move |pt| Edge {from: p.clone(), to:pt.clone()})
-        .collect_vec()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
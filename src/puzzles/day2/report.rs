@@ -0,0 +1,115 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Increasing,
+    Decreasing
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    pub min_step: i64,
+    pub max_step: i64,
+    pub tolerance: usize
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub direction: Direction,
+    pub removed: Vec<usize>
+}
+
+impl Policy {
+    pub const STRICT: Policy = Policy { min_step: 1, max_step: 3, tolerance: 0 };
+    pub const DAMPENED: Policy = Policy { min_step: 1, max_step: 3, tolerance: 1 };
+
+    fn step_ok(&self, a: i64, b: i64, direction: Direction) -> bool {
+        let d = match direction { Direction::Increasing => b.checked_sub(a), Direction::Decreasing => a.checked_sub(b) };
+        d.is_some_and(|d| (self.min_step..=self.max_step).contains(&d))
+    }
+
+    // Fewest removals that keep the steps going `direction`. With at most `tolerance` levels
+    // removed, the kept level before `i` is one of the `tolerance + 1` before it, so this is
+    // linear in the report length for a fixed tolerance.
+    fn fix_towards(&self, report: &[i64], direction: Direction) -> Option<Fix> {
+        let k = self.tolerance;
+        // fewest removals before `i` with `i` kept, and the kept level before it
+        let mut best: Vec<Option<(usize, Option<usize>)>> = Vec::with_capacity(report.len());
+        for i in 0..report.len() {
+            let mut b = (i <= k).then_some((i, None));
+            for p in (i.saturating_sub(k + 1)..i).rev() {
+                let Some((r, _)) = best[p] else { continue };
+                let cost = r + i - p - 1;
+                if cost <= k && b.is_none_or(|(c, _)| cost < c) && self.step_ok(report[p], report[i], direction) {
+                    b = Some((cost, Some(p)));
+                }
+            }
+            best.push(b);
+        }
+        let Some((_, last)) = (0..report.len())
+            .filter_map(|i| best[i].map(|(r, _)| (r + report.len() - 1 - i, i)))
+            .filter(|&(r, _)| r <= k)
+            .min()
+        else {
+            return report.is_empty().then(|| Fix { direction, removed: Vec::new() });
+        };
+        let mut kept = vec![false; report.len()];
+        let mut at = Some(last);
+        while let Some(i) = at {
+            kept[i] = true;
+            at = best[i].and_then(|(_, p)| p);
+        }
+        Some(Fix { direction, removed: (0..report.len()).filter(|&i| !kept[i]).collect() })
+    }
+
+    pub fn fix(&self, report: &[i64]) -> Option<Fix> {
+        [Direction::Increasing, Direction::Decreasing].into_iter()
+            .filter_map(|d| self.fix_towards(report, d))
+            .min_by_key(|f| f.removed.len())
+    }
+
+    pub fn is_safe(&self, report: &[i64]) -> bool {
+        self.fix(report).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn brute_force(p: &Policy, report: &[i64]) -> Option<usize> {
+        (0..=report.len()).rev()
+            .find(|&n| report.iter().combinations(n).any(|kept| {
+                [Direction::Increasing, Direction::Decreasing].into_iter()
+                    .any(|d| kept.iter().tuple_windows().all(|(&&a, &&b)| p.step_ok(a, b, d)))
+            }))
+            .map(|n| report.len() - n)
+            .filter(|&r| r <= p.tolerance)
+    }
+
+    #[test]
+    fn finds_minimal_removals() {
+        let p = Policy::DAMPENED;
+        assert_eq!(p.fix(&[1, 3, 2, 4, 5]), Some(Fix { direction: Direction::Increasing, removed: vec![1] }));
+        assert_eq!(p.fix(&[8, 6, 4, 4, 1]), Some(Fix { direction: Direction::Decreasing, removed: vec![2] }));
+        assert_eq!(p.fix(&[9, 1, 2, 3]), Some(Fix { direction: Direction::Increasing, removed: vec![0] }));
+        assert_eq!(p.fix(&[1, 2, 7, 8, 9]), None);
+        assert_eq!(Policy::STRICT.fix(&[7, 6, 4, 2, 1]).map(|f| f.direction), Some(Direction::Decreasing));
+        assert_eq!(Policy { tolerance: 2, ..p }.fix(&[1, 2, 7, 8, 3]).map(|f| f.removed), Some(vec![2, 3]));
+        assert_eq!(Policy::STRICT.fix(&[]).map(|f| f.removed), Some(vec![]));
+        assert!(!Policy::STRICT.is_safe(&[i64::MIN, i64::MAX]));
+        assert!(!Policy { min_step: i64::MIN, max_step: 0, tolerance: 0 }.is_safe(&[i64::MAX, i64::MIN]));
+
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..500 {
+            let report = (0..rng.gen_range(0..8)).map(|_| rng.gen_range(0..10)).collect_vec();
+            let policy = Policy { min_step: rng.gen_range(0..2), max_step: rng.gen_range(1..4), tolerance: rng.gen_range(0..4) };
+            let fix = policy.fix(&report);
+            assert_eq!(fix.as_ref().map(|f| f.removed.len()), brute_force(&policy, &report), "{report:?} {policy:?}");
+            if let Some(f) = fix {
+                let kept = (0..report.len()).filter(|i| !f.removed.contains(i)).map(|i| report[i]).collect_vec();
+                assert!(kept.iter().tuple_windows().all(|(&a, &b)| policy.step_ok(a, b, f.direction)));
+            }
+        }
+    }
+}
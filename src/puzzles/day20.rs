@@ -1,7 +1,7 @@
 use std::{collections::HashMap, fs};
 
 use itertools::Itertools;
-use solutions::misc::{graph::{Edge, Graph}, grid::Grid, point::Point};
+use solutions::misc::{graph::Graph, grid::Grid, point::Point};
 
 #[derive(Clone, Debug, PartialEq)]
 enum Errors {
@@ -41,20 +41,10 @@ fn _solve(grid:&Grid<char>, max_cheat_distance:usize, threshold:usize) -> Result
 fn shortest_path(grid:&Grid<char>) -> Result<Vec<Point>, Errors> {
     let start = grid.find('S').ok_or(Errors::InvalidGrid)?;
     let end = grid.find('E').ok_or(Errors::InvalidGrid)?;
-    let vs = grid.points_iter().filter(|p| grid[p] != '#').collect_vec();
-    let es = vs.iter().map(|p| (p.clone(), edges(p, grid))).collect::<HashMap<_,_>>();
-    let cs = es.iter().flat_map(|x| x.1.iter().map(|e| (e.clone(),1))).collect();
-    let g = Graph {vertices: vs, edges: es, costs: cs};
+    let g = Graph::from_grid(grid, |&c| c != '#', 1);
     g.find_path(start, end).map_err(|_| Errors::NoPath)
 }
 
-fn edges(p:&Point, grid:&Grid<char>) -> Vec<Edge<Point>> {
-    p.ortho_neighbors().iter()
-        .filter(|pt| grid.try_get(pt).is_some_and(|c| *c != '#'))
-        .map(move |pt| Edge {from: p.clone(), to:pt.clone()})
-        .collect_vec()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
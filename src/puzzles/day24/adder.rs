@@ -0,0 +1,191 @@
+use std::{collections::HashMap, fmt};
+
+use itertools::Itertools;
+use solutions::misc::interner::Interner;
+
+use super::circuit::{Circuit, Gate, GateKind};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    Width { x: usize, y: usize, z: usize },
+    MissingGate { bit: usize, kind: GateKind, inputs: [String; 2], near: Option<String> },
+    WrongOutput { bit: usize, expected: String, found: String }
+}
+
+impl Fault {
+    pub fn bit(&self) -> usize {
+        match self {
+            Fault::Width { .. } => 0,
+            Fault::MissingGate { bit, .. } | Fault::WrongOutput { bit, .. } => *bit
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fault::Width { x, y, z } => write!(f, "bus widths x={x} y={y} z={z} don't form an adder"),
+            Fault::MissingGate { bit, kind, inputs: [a, b], near } => {
+                write!(f, "bit {bit}: no {a} {kind} {b}")?;
+                if let Some(g) = near { write!(f, " (found {g})")?; }
+                Ok(())
+            }
+            Fault::WrongOutput { bit, expected, found } => write!(f, "bit {bit}: {found} should be {expected}")
+        }
+    }
+}
+
+fn describe(c: &Circuit, g: &Gate) -> String {
+    match g.inputs.as_slice() {
+        [a] => format!("{} {} -> {}", g.kind, c.label(*a), c.label(g.out)),
+        ins => format!("{} -> {}", ins.iter().map(|&w| c.label(w)).join(&format!(" {} ", g.kind)), c.label(g.out))
+    }
+}
+
+fn walk(c: &Circuit) -> Result<(), (Fault, Vec<usize>, usize)> {
+    let (xs, ys, zs) = (c.bus('x'), c.bus('y'), c.bus('z'));
+    if xs.is_empty() || ys.len() != xs.len() || zs.len() != xs.len() + 1 {
+        return Err((Fault::Width { x: xs.len(), y: ys.len(), z: zs.len() }, Vec::new(), 0));
+    }
+    let gates = c.gates.iter()
+        .filter_map(|g| match g.inputs.as_slice() {
+            &[a, b] => Some(((g.kind, a.min(b), a.max(b)), g.out)),
+            _ => None
+        })
+        .collect::<HashMap<_,_>>();
+    let mut seen = Vec::new();
+    let find = |seen: &mut Vec<usize>, bit, kind, a: usize, b: usize| {
+        let out = gates.get(&(kind, a.min(b), a.max(b))).copied();
+        seen.extend(out);
+        out.ok_or_else(|| {
+            let near = c.gates.iter().find(|g| g.kind == kind && (g.inputs.contains(&a) || g.inputs.contains(&b)));
+            seen.extend(near.map(|g| g.out));
+            Fault::MissingGate { bit, kind, inputs: [c.label(a).to_string(), c.label(b).to_string()], near: near.map(|g| describe(c, g)) }
+        })
+    };
+    let output = |seen: &mut Vec<usize>, bit, found: usize, expected: usize| {
+        seen.extend([found, expected]);
+        if found == expected { Ok(()) }
+        else { Err(Fault::WrongOutput { bit, expected: c.label(expected).to_string(), found: c.label(found).to_string() }) }
+    };
+    let mut carry = None;
+    for (bit, (&x, &y)) in xs.iter().zip(&ys).enumerate() {
+        seen.clear();
+        seen.extend(carry);
+        // keep checking past the first fault, counting the checks that pass
+        let mut passed = 0;
+        let mut first = None;
+        let mut note = |r: Result<usize, Fault>| match r {
+            Ok(w) => { passed += 1; Some(w) }
+            Err(f) => { first.get_or_insert(f); None }
+        };
+        let sum = note(find(&mut seen, bit, GateKind::Xor, x, y));
+        let generate = note(find(&mut seen, bit, GateKind::And, x, y));
+        let cout = match carry {
+            None => {
+                if let Some(s) = sum { note(output(&mut seen, bit, s, zs[bit]).map(|_| s)); }
+                generate
+            }
+            Some(cin) => {
+                let z = sum.and_then(|s| note(find(&mut seen, bit, GateKind::Xor, s, cin)));
+                if let Some(z) = z { note(output(&mut seen, bit, z, zs[bit]).map(|_| z)); }
+                let propagate = sum.and_then(|s| note(find(&mut seen, bit, GateKind::And, s, cin)));
+                generate.zip(propagate).and_then(|(g, p)| note(find(&mut seen, bit, GateKind::Or, g, p)))
+            }
+        };
+        match (first, cout) {
+            (Some(f), _) => return Err((f, seen, passed)),
+            (None, c) => carry = c
+        }
+    }
+    seen.clear();
+    let top = xs.len();
+    output(&mut seen, top, carry.unwrap(), zs[top]).map_err(|f| (f, seen, 0))
+}
+
+pub fn verify(c: &Circuit) -> Result<(), Fault> {
+    walk(c).map_err(|(f, _, _)| f)
+}
+
+fn fix(c: &Circuit, budget: usize, swaps: &mut Vec<(usize, usize)>) -> Option<Circuit> {
+    let Err((fault, suspects, passed)) = walk(c) else { return Some(c.clone()) };
+    if budget == 0 { return None; }
+    let outputs = c.gates.iter().map(|g| g.out).sorted().collect_vec();
+    for &s in suspects.iter().unique().filter(|&&s| c.driver(s).is_some()) {
+        for &w in outputs.iter().filter(|&&w| w != s) {
+            // swaps creating loops are rejected by the circuit itself
+            let Ok(swapped) = c.swap_outputs(s, w) else { continue };
+            if walk(&swapped).is_err_and(|(f, _, p)| f.bit() < fault.bit() || (f.bit() == fault.bit() && p <= passed)) { continue; }
+            swaps.push((s.min(w), s.max(w)));
+            if let Some(fixed) = fix(&swapped, budget - 1, swaps) { return Some(fixed); }
+            swaps.pop();
+        }
+    }
+    None
+}
+
+// Each swap has to move the first fault to a later bit, or pass more checks at the failing bit
+// (when one bit needs two swaps), which keeps the search local to that bit.
+pub fn repair(c: &Circuit, max_swaps: usize) -> Option<(Vec<(usize, usize)>, Circuit)> {
+    (0..=max_swaps).find_map(|budget| {
+        let mut swaps = Vec::new();
+        fix(c, budget, &mut swaps).map(|fixed| (swaps, fixed))
+    })
+}
+
+pub fn ripple_carry_adder(bits: usize) -> Circuit {
+    let carry = |i: usize| if i + 1 == bits { format!("z{bits:02}") } else { format!("c{i:02}") };
+    let mut lines = vec!["x00 XOR y00 -> z00".to_string(), format!("x00 AND y00 -> {}", carry(0))];
+    for i in 1..bits {
+        let cin = carry(i - 1);
+        lines.push(format!("x{i:02} XOR y{i:02} -> s{i:02}"));
+        lines.push(format!("x{i:02} AND y{i:02} -> g{i:02}"));
+        lines.push(format!("s{i:02} XOR {cin} -> z{i:02}"));
+        lines.push(format!("s{i:02} AND {cin} -> p{i:02}"));
+        lines.push(format!("g{i:02} OR p{i:02} -> {}", carry(i)));
+    }
+    let mut wires = Interner::new();
+    let gates = Circuit::parse_gates(&mut wires, &lines.join("\n")).expect("generated gates parse");
+    Circuit::new(wires, gates).expect("generated adder is acyclic")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::day24::equivalence::check_adder;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn swap(c: &Circuit, a: &str, b: &str) -> Circuit {
+        c.swap_outputs(c.wire(a).unwrap(), c.wire(b).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn verifies_canonical_adder() {
+        for bits in [1, 2, 12] {
+            let c = ripple_carry_adder(bits);
+            assert_eq!(verify(&c), Ok(()));
+            assert_eq!(check_adder(&c, 50, &mut StdRng::seed_from_u64(7)), Ok(()));
+        }
+        assert_eq!(ripple_carry_adder(8).add(200, 100), Ok(300));
+    }
+
+    #[test]
+    fn locates_and_repairs_swaps() {
+        let good = ripple_carry_adder(12);
+        let bad = swap(&swap(&good, "z03", "c03"), "s05", "g05");
+        assert_eq!(verify(&bad), Err(Fault::WrongOutput { bit: 3, expected: "z03".to_string(), found: "c03".to_string() }));
+        assert!(check_adder(&bad, 50, &mut StdRng::seed_from_u64(7)).is_err());
+        let (swaps, fixed) = repair(&bad, 4).unwrap();
+        let labels = swaps.iter().flat_map(|&(a, b)| [a, b]).map(|w| fixed.label(w)).sorted().collect_vec();
+        assert_eq!(labels, ["c03", "g05", "s05", "z03"]);
+        assert_eq!(check_adder(&fixed, 50, &mut StdRng::seed_from_u64(7)), Ok(()));
+        assert!(repair(&bad, 1).is_none());
+        let second = swap(&good, "s05", "g05");
+        assert!(matches!(verify(&second), Err(Fault::MissingGate { bit: 5, kind: GateKind::Xor, near: Some(_), .. })));
+        let both = swap(&second, "z05", "c05");
+        let (swaps, fixed) = repair(&both, 2).unwrap();
+        let labels = swaps.iter().flat_map(|&(a, b)| [a, b]).map(|w| fixed.label(w)).sorted().collect_vec();
+        assert_eq!(labels, ["c05", "g05", "s05", "z05"]);
+        assert!(repair(&both, 1).is_none());
+    }
+}
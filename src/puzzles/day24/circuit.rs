@@ -0,0 +1,198 @@
+use std::{collections::HashMap, fmt};
+
+use itertools::Itertools;
+use solutions::misc::{graph::{algorithms::Cycle, Graph}, interner::Interner};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GateKind { And, Or, Xor, Nand, Nor, Xnor, Not }
+
+impl GateKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "AND" => Some(GateKind::And),
+            "OR" => Some(GateKind::Or),
+            "XOR" => Some(GateKind::Xor),
+            "NAND" => Some(GateKind::Nand),
+            "NOR" => Some(GateKind::Nor),
+            "XNOR" => Some(GateKind::Xnor),
+            "NOT" => Some(GateKind::Not),
+            _ => None
+        }
+    }
+
+    pub fn arity(self) -> usize {
+        if self == GateKind::Not { 1 } else { 2 }
+    }
+
+    pub fn eval(self, inputs: &[bool]) -> bool {
+        match (self, inputs) {
+            (GateKind::Not, [a]) => !a,
+            (GateKind::And, [a, b]) => a & b,
+            (GateKind::Or, [a, b]) => a | b,
+            (GateKind::Xor, [a, b]) => a ^ b,
+            (GateKind::Nand, [a, b]) => !(a & b),
+            (GateKind::Nor, [a, b]) => !(a | b),
+            (GateKind::Xnor, [a, b]) => !(a ^ b),
+            _ => panic!("{self:?} applied to {} inputs", inputs.len())
+        }
+    }
+}
+
+impl fmt::Display for GateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format!("{self:?}").to_uppercase())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gate {
+    pub kind: GateKind,
+    pub inputs: Vec<usize>,
+    pub out: usize
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    Parse(String),
+    Loop(Vec<String>),
+    Undriven(String),
+    MultipleDrivers(String),
+    Arity { out: String, expected: usize, found: usize }
+}
+
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    pub wires: Interner,
+    pub gates: Vec<Gate>,
+    driver: Vec<Option<usize>>,
+    order: Vec<usize>
+}
+
+fn bus_bits(wires: &Interner, prefix: char) -> Vec<usize> {
+    (0..wires.len())
+        .filter_map(|w| {
+            let l = wires.label(w);
+            let n = l.strip_prefix(prefix)?.parse::<usize>().ok()?;
+            Some((n, w))
+        })
+        .sorted()
+        .map(|(_, w)| w)
+        .collect()
+}
+
+impl Circuit {
+    pub fn new(wires: Interner, gates: Vec<Gate>) -> Result<Self, CircuitError> {
+        let mut driver = vec![None; wires.len()];
+        for (i, g) in gates.iter().enumerate() {
+            if g.inputs.len() != g.kind.arity() {
+                return Err(CircuitError::Arity { out: wires.label(g.out).to_string(), expected: g.kind.arity(), found: g.inputs.len() });
+            }
+            if driver[g.out].replace(i).is_some() { return Err(CircuitError::MultipleDrivers(wires.label(g.out).to_string())); }
+        }
+        let deps = Graph::from_edges(0..wires.len(), gates.iter().flat_map(|g| g.inputs.iter().map(|&i| (i, g.out, ()))))
+            .expect("wire ids come from the interner");
+        let order = deps.topological_sort()
+            .map_err(|Cycle(ws)| CircuitError::Loop(ws.into_iter().map(|w| wires.label(w).to_string()).collect()))?
+            .into_iter()
+            .filter_map(|w| driver[w])
+            .collect();
+        Ok(Circuit { wires, gates, driver, order })
+    }
+
+    pub fn parse_gates(wires: &mut Interner, input: &str) -> Result<Vec<Gate>, CircuitError> {
+        input.lines().filter(|l| !l.trim().is_empty()).map(|l| {
+            let err = || CircuitError::Parse(l.to_string());
+            let (lhs, out) = l.split_once(" -> ").ok_or_else(err)?;
+            let tokens = lhs.split_whitespace().collect_vec();
+            let (kind, ins) = match tokens.as_slice() {
+                [op, a] => (GateKind::parse(op).ok_or_else(err)?, vec![*a]),
+                [a, op, b] => (GateKind::parse(op).ok_or_else(err)?, vec![*a, *b]),
+                _ => return Err(err())
+            };
+            Ok(Gate { kind, inputs: ins.into_iter().map(|w| wires.intern(w)).collect(), out: wires.intern(out.trim()) })
+        })
+        .collect()
+    }
+
+    pub fn wire(&self, label: &str) -> Option<usize> { self.wires.get(label) }
+
+    pub fn label(&self, wire: usize) -> &str { self.wires.label(wire) }
+
+    pub fn driver(&self, wire: usize) -> Option<&Gate> {
+        self.driver.get(wire).copied().flatten().map(|g| &self.gates[g])
+    }
+
+    pub fn inputs(&self) -> Vec<usize> {
+        (0..self.wires.len()).filter(|&w| self.driver[w].is_none()).collect()
+    }
+
+    pub fn bus(&self, prefix: char) -> Vec<usize> {
+        bus_bits(&self.wires, prefix)
+    }
+
+    pub fn evaluate(&self, inputs: &HashMap<usize, bool>) -> Result<Vec<bool>, CircuitError> {
+        let mut values = vec![None; self.wires.len()];
+        inputs.iter().for_each(|(&w, &v)| values[w] = Some(v));
+        for &g in &self.order {
+            let gate = &self.gates[g];
+            let ins = gate.inputs.iter()
+                .map(|&w| values[w].ok_or_else(|| CircuitError::Undriven(self.label(w).to_string())))
+                .collect::<Result<Vec<_>, _>>()?;
+            values[gate.out] = Some(gate.kind.eval(&ins));
+        }
+        Ok(values.into_iter().map(|v| v.unwrap_or(false)).collect())
+    }
+
+    pub fn eval_numbers(&self, buses: &[(char, u64)]) -> Result<u64, CircuitError> {
+        let inputs = buses.iter()
+            .flat_map(|&(p, n)| self.bus(p).into_iter().enumerate().map(move |(i, w)| (w, i < 64 && n >> i & 1 == 1)))
+            .collect();
+        let values = self.evaluate(&inputs)?;
+        Ok(read_bus(&values, &self.bus('z')))
+    }
+
+    pub fn add(&self, x: u64, y: u64) -> Result<u64, CircuitError> {
+        self.eval_numbers(&[('x', x), ('y', y)])
+    }
+
+    pub fn swap_outputs(&self, a: usize, b: usize) -> Result<Self, CircuitError> {
+        let gates = self.gates.iter().cloned()
+            .map(|mut g| { if g.out == a { g.out = b } else if g.out == b { g.out = a }; g })
+            .collect();
+        Circuit::new(self.wires.clone(), gates)
+    }
+}
+
+pub fn read_bus(values: &[bool], bits: &[usize]) -> u64 {
+    bits.iter().rev().fold(0, |acc, &w| (acc << 1) | values[w] as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circuit(gates: &str) -> Result<Circuit, CircuitError> {
+        let mut wires = Interner::new();
+        let gates = Circuit::parse_gates(&mut wires, gates)?;
+        Circuit::new(wires, gates)
+    }
+
+    #[test]
+    fn evaluates_in_dependency_order() {
+        let c = circuit("t XOR y01 -> z01\nx00 AND y00 -> t\nx00 XOR y00 -> z00\nNOT x00 -> n\nn NOR y00 -> z02").unwrap();
+        assert_eq!(c.bus('x').len(), 1);
+        assert_eq!(c.eval_numbers(&[('x', 1), ('y', 1)]), Ok(0b010));
+        assert_eq!(c.eval_numbers(&[('x', 0), ('y', 2)]), Ok(0b010));
+        assert_eq!(c.add(1, 0), Ok(0b101));
+        let missing = c.evaluate(&HashMap::new());
+        assert!(matches!(missing, Err(CircuitError::Undriven(_))));
+    }
+
+    #[test]
+    fn reports_structural_errors() {
+        assert!(matches!(circuit("a AND b -> c\nc OR d -> a"), Err(CircuitError::Loop(ws)) if ws.len() == 2));
+        assert_eq!(circuit("a AND b -> c\na OR b -> c").unwrap_err(), CircuitError::MultipleDrivers("c".to_string()));
+        assert!(matches!(circuit("a FOO b -> c"), Err(CircuitError::Parse(_))));
+        assert!(matches!(circuit("NOT a b -> c"), Err(CircuitError::Parse(_))));
+    }
+}
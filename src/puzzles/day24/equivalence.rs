@@ -0,0 +1,113 @@
+use rand::Rng;
+
+use super::circuit::{read_bus, Circuit, CircuitError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mismatch {
+    pub x: u64,
+    pub y: u64,
+    pub expected: u64,
+    pub found: u64,
+    pub bit: usize
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EquivalenceError {
+    Circuit(CircuitError),
+    Mismatch(Mismatch),
+    TooWide(usize),
+    TooManyInputs(usize)
+}
+
+pub const EXHAUSTIVE_WIDTH: usize = 8;
+
+pub struct Tester<'a, F> {
+    circuit: &'a Circuit,
+    reference: F,
+    width: usize,
+    buses: [Vec<usize>; 3]
+}
+
+fn mask(bits: usize) -> u64 {
+    if bits >= 64 { u64::MAX } else { (1 << bits) - 1 }
+}
+
+impl<'a, F: Fn(u64, u64) -> u64> Tester<'a, F> {
+    pub fn new(circuit: &'a Circuit, reference: F) -> Result<Self, EquivalenceError> {
+        let buses = ['x', 'y', 'z'].map(|p| circuit.bus(p));
+        let width = buses[0].len().max(buses[1].len());
+        if width > 63 { return Err(EquivalenceError::TooWide(width)); }
+        Ok(Tester { circuit, reference, width, buses })
+    }
+
+    pub fn width(&self) -> usize { self.width }
+
+    pub fn check(&self, x: u64, y: u64) -> Result<(), EquivalenceError> {
+        let [xs, ys, zs] = &self.buses;
+        let inputs = [(xs, x), (ys, y)].into_iter()
+            .flat_map(|(bus, n)| bus.iter().enumerate().map(move |(i, &w)| (w, n >> i & 1 == 1)))
+            .collect();
+        let values = self.circuit.evaluate(&inputs).map_err(EquivalenceError::Circuit)?;
+        let found = read_bus(&values, zs);
+        let expected = (self.reference)(x, y) & mask(zs.len());
+        if found == expected { return Ok(()); }
+        let bit = (found ^ expected).trailing_zeros() as usize;
+        Err(EquivalenceError::Mismatch(Mismatch { x, y, expected, found, bit }))
+    }
+
+    pub fn check_all(&self, pairs: impl IntoIterator<Item = (u64, u64)>) -> Result<(), EquivalenceError> {
+        pairs.into_iter().try_for_each(|(x, y)| self.check(x, y))
+    }
+
+    pub fn edge_cases(&self) -> Vec<(u64, u64)> {
+        let max = mask(self.width);
+        let mut pairs = vec![(0, 0), (max, 0), (0, max), (max, max), (max, 1), (1, max)];
+        for i in 0..self.width {
+            let bit = 1 << i;
+            pairs.extend([(bit, 0), (0, bit), (bit, bit), (max >> i << i, bit), (bit, max >> i << i)]);
+        }
+        pairs
+    }
+
+    pub fn random(&self, trials: usize, rng: &mut impl Rng) -> Result<(), EquivalenceError> {
+        let max = mask(self.width);
+        self.check_all((0..trials).map(|_| (rng.gen_range(0..=max), rng.gen_range(0..=max))))
+    }
+
+    pub fn exhaustive(&self) -> Result<(), EquivalenceError> {
+        if self.width > EXHAUSTIVE_WIDTH { return Err(EquivalenceError::TooManyInputs(self.width)); }
+        let max = mask(self.width);
+        self.check_all((0..=max).flat_map(|x| (0..=max).map(move |y| (x, y))))
+    }
+
+    pub fn run(&self, trials: usize, rng: &mut impl Rng) -> Result<(), EquivalenceError> {
+        self.check_all(self.edge_cases())?;
+        if self.width <= EXHAUSTIVE_WIDTH { self.exhaustive() } else { self.random(trials, rng) }
+    }
+}
+
+pub fn check_adder(c: &Circuit, trials: usize, rng: &mut impl Rng) -> Result<(), EquivalenceError> {
+    Tester::new(c, |x, y| x + y)?.run(trials, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::day24::adder::ripple_carry_adder;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn finds_first_failing_bit() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(check_adder(&ripple_carry_adder(6), 0, &mut rng), Ok(()));
+        assert_eq!(check_adder(&ripple_carry_adder(40), 200, &mut rng), Ok(()));
+        let good = ripple_carry_adder(6);
+        let bad = good.swap_outputs(good.wire("z02").unwrap(), good.wire("c02").unwrap()).unwrap();
+        let Err(EquivalenceError::Mismatch(m)) = check_adder(&bad, 0, &mut rng) else { panic!("swap not detected") };
+        assert_eq!(m.bit, 2);
+        assert_eq!(m.expected, m.x + m.y);
+        let t = Tester::new(&good, |x, y| x ^ y).unwrap();
+        assert!(matches!(t.check(1, 1), Err(EquivalenceError::Mismatch(Mismatch { bit: 1, .. }))));
+        assert_eq!(Tester::new(&ripple_carry_adder(12), |x, y| x + y).unwrap().exhaustive(), Err(EquivalenceError::TooManyInputs(12)));
+    }
+}
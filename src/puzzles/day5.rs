@@ -1,44 +1,38 @@
-use std::{cmp::Ordering, collections::HashSet, convert::identity, fs};
+pub mod rules;
+
+use std::fs;
+
+use rules::{OrderError, Page, Rules};
 
 pub fn solve() {
     let data = fs::read_to_string("../inputs/day5").expect("Should be able to read input");
-    println!("P1: {p1}", p1 = part1(&data));
-    println!("P2: {p2}", p2 = part2(&data));
+    println!("P1: {p1:?}", p1 = part1(&data));
+    println!("P2: {p2:?}", p2 = part2(&data));
 }
 
-fn part1(data:&String) -> usize { _solve(data, identity) }
-fn part2(data:&String) -> usize { _solve(data,|b| !b) }
+fn part1(data:&String) -> Result<usize, OrderError> { _solve(data, true) }
+fn part2(data:&String) -> Result<usize, OrderError> { _solve(data, false) }
 
-fn _solve(data:&String, f:impl Fn(bool) -> bool) -> usize {
-    let (cmp, originals) = parse(data);
-    let mut sorted: Vec<_>= originals.iter().map(|s| s.clone()).collect();
-    sorted.iter_mut().for_each(|s| s.sort_unstable_by(&cmp));
-    sorted.iter().zip(originals)
-        .filter(|(ss,o)| f(ss.eq(&o)))
-        .map(|(s,_o)| s[s.len()/2] as usize)
+fn _solve(data:&String, ordered:bool) -> Result<usize, OrderError> {
+    let (rules, updates) = parse(data);
+    updates.iter()
+        .filter(|u| rules.is_ordered(u) == ordered)
+        .map(|u| match rules.repair(u) {
+            // when the rules allow several orders, settle for the smallest-page-first one
+            Ok(s) | Err(OrderError::Ambiguous { order: s, .. }) => Ok(s[s.len()/2] as usize),
+            Err(e) => Err(e)
+        })
         .sum()
 }
 
-fn get_cmp(rules:HashSet<(u8,u8)>) -> impl Fn(&u8,&u8) -> Ordering {
-    move |a:&u8, b:&u8| 
-    if rules.contains(&(*a,*b)) { Ordering::Less }
-    else if rules.contains(&(*b,*a)) {Ordering::Greater}
-    else {Ordering::Equal}
-}
-
-fn parse(data:&String) -> (impl Fn(&u8,&u8) -> Ordering, Vec<Vec<u8>>) {
+fn parse(data:&String) -> (Rules, Vec<Vec<Page>>) {
     let mut split = data.split("\n\n");
     let (rules_str, seq_str) = (split.next().unwrap(), split.next().unwrap());
-    let rules = rules_str.lines()
-        .map(|l| {
-            let mut s = l.split('|').map(|x| x.parse::<u8>().unwrap());
-            (s.next().unwrap(), s.next().unwrap())
-        })
-        .collect::<HashSet<_>>();
+    let rules = Rules::new(rules_str.lines().map(|l| l.parse().unwrap()));
     let seq = seq_str.lines()
-        .map(|l| l.split(',').map(|x|x.parse::<u8>().unwrap()).collect())
+        .map(|l| l.split(',').map(|x|x.parse::<Page>().unwrap()).collect())
         .collect();
-    (get_cmp(rules), seq)
+    (rules, seq)
 }
 
 
@@ -54,7 +48,9 @@ mod tests {
 4|5
 
 1,3,4,2,5");
-        assert_eq!(part2(&data), 3);
+        assert_eq!(part2(&data), Ok(3));
+        assert_eq!(part2(&String::from("1|2\n1|3\n\n3,1,2")), Ok(2));
+        assert!(matches!(part2(&String::from("1|2\n2|1\n\n2,1")), Err(OrderError::Cycle(_))));
     }
 
     #[test]
@@ -88,7 +84,7 @@ mod tests {
 61,13,29
 97,13,75,29,47
 ");
-        assert_eq!(part1(&data), 143);
-        assert_eq!(part2(&data), 123);
+        assert_eq!(part1(&data), Ok(143));
+        assert_eq!(part2(&data), Ok(123));
     }
 }
@@ -0,0 +1,129 @@
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap, HashSet}, fmt, str::FromStr};
+
+use itertools::Itertools;
+use solutions::misc::graph::{algorithms::Cycle, Graph};
+
+pub type Page = u8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    pub before: Page,
+    pub after: Page
+}
+
+#[derive(Debug)]
+pub struct ParseRuleError;
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (b, a) = s.split_once('|').ok_or(ParseRuleError)?;
+        Ok(Rule { before: b.trim().parse().map_err(|_| ParseRuleError)?, after: a.trim().parse().map_err(|_| ParseRuleError)? })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}", self.before, self.after)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    Cycle(Vec<Page>),
+    // `order` puts the smallest page first wherever the rules allow a choice.
+    Ambiguous { order: Vec<Page>, unordered: (Page, Page) },
+    Duplicate(Page)
+}
+
+pub struct Rules {
+    rules: HashSet<Rule>
+}
+
+impl Rules {
+    pub fn new(rules: impl IntoIterator<Item = Rule>) -> Self {
+        Rules { rules: rules.into_iter().collect() }
+    }
+
+    fn has(&self, before: Page, after: Page) -> bool {
+        self.rules.contains(&Rule { before, after })
+    }
+
+    pub fn violations(&self, update: &[Page]) -> Vec<Rule> {
+        update.iter().enumerate()
+            .flat_map(|(i, &a)| update[i + 1..].iter().map(move |&b| Rule { before: b, after: a }))
+            .filter(|r| self.rules.contains(r))
+            .collect()
+    }
+
+    pub fn is_ordered(&self, update: &[Page]) -> bool {
+        self.violations(update).is_empty()
+    }
+
+    pub fn order(&self, pages: &[Page]) -> Result<Vec<Page>, OrderError> {
+        if let Some(&p) = pages.iter().duplicates().next() { return Err(OrderError::Duplicate(p)); }
+        let edges = self.rules.iter()
+            .filter(|r| pages.contains(&r.before) && pages.contains(&r.after))
+            .map(|r| (r.before, r.after))
+            .sorted()
+            .collect_vec();
+        let mut indegree = pages.iter().map(|&p| (p, 0)).collect::<HashMap<_, _>>();
+        edges.iter().for_each(|(_, a)| *indegree.get_mut(a).unwrap() += 1);
+        let mut ready = indegree.iter().filter(|&(_, &d)| d == 0).map(|(&p, _)| Reverse(p)).collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(pages.len());
+        while let Some(Reverse(p)) = ready.pop() {
+            order.push(p);
+            for &(_, a) in edges.iter().filter(|(b, _)| *b == p) {
+                let d = indegree.get_mut(&a).unwrap();
+                *d -= 1;
+                if *d == 0 { ready.push(Reverse(a)); }
+            }
+        }
+        if order.len() < pages.len() {
+            let Cycle(c) = Graph::from_edges(pages.iter().copied(), edges.into_iter().map(|(b, a)| (b, a, ())))
+                .expect("rules only connect pages of the update")
+                .find_cycle()
+                .expect("pages left unsorted lie on a cycle");
+            return Err(OrderError::Cycle(c));
+        }
+        match order.windows(2).find(|w| !self.has(w[0], w[1])) {
+            Some(w) => Err(OrderError::Ambiguous { unordered: (w[0], w[1]), order }),
+            None => Ok(order)
+        }
+    }
+
+    pub fn repair(&self, update: &[Page]) -> Result<Vec<Page>, OrderError> {
+        if let Some(&p) = update.iter().duplicates().next() { return Err(OrderError::Duplicate(p)); }
+        if self.is_ordered(update) { Ok(update.to_vec()) } else { self.order(update) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(s: &str) -> Rules {
+        Rules::new(s.split(',').map(|r| r.parse().unwrap()))
+    }
+
+    #[test]
+    fn validates_and_repairs() {
+        let r = rules("97|75,75|47,97|47,47|61,75|61,97|61");
+        assert_eq!(r.violations(&[75, 97, 47, 61]), [Rule { before: 97, after: 75 }]);
+        assert_eq!(r.violations(&[61, 47, 75]).iter().map(Rule::to_string).collect::<Vec<_>>(), ["47|61", "75|61", "75|47"]);
+        assert_eq!(r.repair(&[75, 97, 47, 61]), Ok(vec![97, 75, 47, 61]));
+        assert_eq!(r.repair(&[47, 61]), Ok(vec![47, 61]));
+
+        let chain = rules("1|2,2|3,3|4,4|5");
+        assert!(!chain.is_ordered(&[1, 3, 4, 2, 5]));
+        assert_eq!(chain.order(&[1, 3, 4, 2, 5]), Ok(vec![1, 2, 3, 4, 5]));
+        assert_eq!(chain.order(&[5, 1, 3]), Err(OrderError::Ambiguous { order: vec![1, 3, 5], unordered: (1, 3) }));
+        assert_eq!(chain.repair(&[2, 1, 2]), Err(OrderError::Duplicate(2)));
+
+        let cyclic = rules("1|2,2|3,3|1");
+        assert!(matches!(cyclic.order(&[3, 1, 2]), Err(OrderError::Cycle(c)) if c.len() == 3));
+        assert_eq!(cyclic.order(&[2, 1]), Ok(vec![1, 2]));
+        assert!(matches!(rules("1|2,1|3").order(&[1, 3, 2]), Err(OrderError::Ambiguous { unordered: (2, 3), .. })));
+    }
+}
@@ -1,6 +1,10 @@
-use std::{collections::HashSet, fs, hash::Hash};
+pub mod patrol;
 
-use solutions::misc::{grid::Grid, point::Point};
+use std::{collections::HashSet, fs};
+
+use solutions::misc::{grid::Grid, point::NORTH};
+
+use patrol::{Guard, Patrol};
 
 pub fn solve() {
     let data = fs::read_to_string("../inputs/day6").expect("Should be able to read input");
@@ -9,61 +13,21 @@ pub fn solve() {
     println!("P2: {p2}", p2 = part2(&grid));
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Guard {
-    pos: Point,
-    dir: Point,
-}
-impl Guard {
-    fn turn_right(self) -> Self {
-        Guard {pos: self.pos, dir: self.dir.rotate_cw()}
-    }
-    
-    fn next(self) ->  Self {
-        Guard {pos: self.pos + self.dir, dir: self.dir}
-    }
-}
-impl Hash for Guard {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.pos.hash(state);
-        self.dir.hash(state);
-    }
+fn start(grid:&Grid<char>) -> Guard {
+    Guard { pos:grid.find('^').unwrap(), dir:NORTH }
 }
 
 fn part1(grid:&Grid<char>) -> usize {
-    let initial = Guard { pos:grid.find('^').unwrap(), dir:Point::new(0, -1) };
-    exit_path(initial, grid,  None)
+    Patrol::new(grid).steps(start(grid))
         .unwrap()
         .iter()
+        .map(|g| g.pos)
         .collect::<HashSet<_>>()
         .len()
 }
 
-fn exit_path(initial:Guard, grid:&Grid<char>, extra_obstacle:Option<Point>) -> Option<Vec<Point>> {
-    let mut visited = HashSet::new();
-    let mut g = initial;
-    let mut path = Vec::new();
-    while grid.contains(&g.pos) {
-        let next = g.next();
-        if Some(&'#') == grid.try_get(next.pos) || extra_obstacle.is_some_and(|p| p == next.pos) {
-            if !visited.insert(g) { return None; }
-            g = g.turn_right();
-        } else {
-            path.push(g.pos);
-            g = next;
-        }
-    }
-    Some(path)
-}
-
 fn part2(grid:&Grid<char>) -> usize {
-    let initial = Guard { pos:grid.find('^').unwrap(), dir:Point::new(0, -1) };
-    let path = exit_path(initial, grid, None).unwrap();
-    let mut cs = HashSet::new();
-    path.iter().skip(1).for_each(|&p| {
-        if exit_path(initial, grid, Some(p)).is_none() { cs.insert(p); }
-    });
-    cs.len()
+    Patrol::new(grid).loop_obstacles(start(grid)).len()
 }
 
 #[cfg(test)]
@@ -0,0 +1,142 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use solutions::misc::{grid::Grid, point::{Point, ORTHO_DIR}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Guard {
+    pub pos: Point,
+    pub dir: Point,
+}
+
+impl Guard {
+    pub fn turn_right(self) -> Self {
+        Guard {pos: self.pos, dir: self.dir.rotate_cw()}
+    }
+
+    pub fn next(self) -> Self {
+        Guard {pos: self.pos + self.dir, dir: self.dir}
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Exit(Vec<Guard>),
+    Loop(Vec<Guard>)
+}
+
+impl Outcome {
+    pub fn is_loop(&self) -> bool { matches!(self, Outcome::Loop(_)) }
+}
+
+fn dir_index(dir: Point) -> usize {
+    ORTHO_DIR.iter().position(|&d| d == dir).expect("guard faces an orthogonal direction")
+}
+
+// Lab map with, for every cell and direction, the cell where a guard walking that way
+// stops in front of an obstacle (`None` when it walks off the map instead).
+pub struct Patrol {
+    obstacles: Grid<bool>,
+    jumps: Grid<[Option<Point>; 4]>
+}
+
+impl Patrol {
+    pub fn new(grid: &Grid<char>) -> Self {
+        Patrol::from_obstacles(Grid { width: grid.width, height: grid.height, cells: grid.cells.iter().map(|&c| c == '#').collect() })
+    }
+
+    pub fn from_obstacles(obstacles: Grid<bool>) -> Self {
+        let mut jumps = Grid::<[Option<Point>; 4]>::new(obstacles.width, obstacles.height, [None; 4]);
+        for (i, &d) in ORTHO_DIR.iter().enumerate() {
+            // cells further along `d` first, so the jump from the next cell is already known
+            for p in obstacles.points_iter().sorted_by_key(|p| -p.dot(d)) {
+                let next = p + d;
+                jumps[&p][i] = match obstacles.try_get(next) {
+                    None => None,
+                    Some(true) => Some(p),
+                    Some(false) => jumps[&next][i]
+                };
+            }
+        }
+        Patrol { obstacles, jumps }
+    }
+
+    pub fn is_obstacle(&self, p: Point) -> bool {
+        self.obstacles.try_get(p).copied().unwrap_or(false)
+    }
+
+    pub fn stop(&self, g: Guard, extra: Option<Point>) -> Option<Point> {
+        let base = self.jumps[&g.pos][dir_index(g.dir)];
+        let ahead = |o: Point| {
+            let d = o - g.pos;
+            d.cross(g.dir) == 0 && d.dot(g.dir) > 0 && base.is_none_or(|b| d.dot(g.dir) <= (b - g.pos).dot(g.dir))
+        };
+        match extra {
+            Some(o) if self.obstacles.contains(&o) && ahead(o) => Some(o - g.dir),
+            _ => base
+        }
+    }
+
+    pub fn run(&self, start: Guard, extra: Option<Point>) -> Outcome {
+        let mut turns = Vec::new();
+        let mut seen = HashMap::new();
+        let mut g = start;
+        while let Some(p) = self.stop(g, extra) {
+            let at = Guard { pos: p, dir: g.dir };
+            if let Some(&i) = seen.get(&at) { return Outcome::Loop(turns.split_off(i)); }
+            seen.insert(at, turns.len());
+            turns.push(at);
+            g = at.turn_right();
+        }
+        Outcome::Exit(turns)
+    }
+
+    pub fn steps(&self, start: Guard) -> Option<Vec<Guard>> {
+        let Outcome::Exit(turns) = self.run(start, None) else { return None };
+        let mut steps = Vec::new();
+        let mut g = start;
+        for t in turns.iter().map(|t| Some(t.pos)).chain([None]) {
+            while Some(g.pos) != t && self.obstacles.contains(&g.pos) {
+                steps.push(g);
+                g = g.next();
+            }
+            g = g.turn_right();
+        }
+        Some(steps)
+    }
+
+    // Cells where one more obstacle traps the guard in a loop. Each candidate is the first cell
+    // the guard would enter on its original route, so the search resumes from the step before it.
+    pub fn loop_obstacles(&self, start: Guard) -> Vec<Point> {
+        let Some(steps) = self.steps(start) else { return Vec::new() };
+        let mut tried = HashSet::from([start.pos]);
+        steps.iter()
+            .filter(|g| self.obstacles.contains(&g.next().pos) && tried.insert(g.next().pos))
+            .filter(|&&g| self.run(g, Some(g.next().pos)).is_loop())
+            .map(|g| g.next().pos)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solutions::misc::point::{EAST, NORTH};
+
+    #[test]
+    fn jumps_and_cycles() {
+        let grid = Grid::char_grid("....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^.....\n........#.\n#.........\n......#...");
+        let patrol = Patrol::new(&grid);
+        let start = Guard { pos: Point::new(4, 6), dir: NORTH };
+        assert_eq!(patrol.stop(start, None), Some(Point::new(4, 1)));
+        assert_eq!(patrol.stop(start, Some(Point::new(4, 3))), Some(Point::new(4, 4)));
+        assert_eq!(patrol.stop(start, Some(Point::new(4, 8))), Some(Point::new(4, 1)));
+        assert_eq!(patrol.stop(Guard { pos: Point::new(4, 6), dir: EAST }, None), None);
+        let Outcome::Exit(turns) = patrol.run(start, None) else { panic!("sample guard leaves") };
+        assert_eq!(turns.len(), 10);
+        assert_eq!(patrol.steps(start).unwrap().iter().map(|g| g.pos).unique().count(), 41);
+        let Outcome::Loop(cycle) = patrol.run(start, Some(Point::new(3, 6))) else { panic!("obstacle should trap the guard") };
+        assert_eq!(cycle.iter().map(|g| g.pos).collect_vec(), [Point::new(4, 1), Point::new(8, 1), Point::new(8, 6), Point::new(4, 6)]);
+        assert_eq!(patrol.loop_obstacles(start).len(), 6);
+    }
+}
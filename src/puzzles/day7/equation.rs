@@ -0,0 +1,209 @@
+use std::ops::ControlFlow;
+
+use itertools::Itertools;
+use num::integer::Roots;
+
+pub type Number = i128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inverse {
+    None,
+    One(Number),
+    // Every left operand in the operator's domain.
+    Any
+}
+
+// A binary operator, applied left to right. `apply` is `None` outside the operator's domain
+// or on overflow, and `inverse` must agree with it so the search can work backwards.
+pub trait Operator {
+    fn symbol(&self) -> &'static str;
+    fn apply(&self, acc: Number, x: Number) -> Option<Number>;
+    fn inverse(&self, target: Number, x: Number) -> Inverse;
+}
+
+fn some(n: Option<Number>) -> Inverse { n.map_or(Inverse::None, Inverse::One) }
+
+pub struct Add;
+pub struct Mul;
+pub struct Concat;
+pub struct Sub;
+pub struct Div;
+pub struct Pow;
+
+impl Operator for Add {
+    fn symbol(&self) -> &'static str { "+" }
+    fn apply(&self, acc: Number, x: Number) -> Option<Number> { acc.checked_add(x) }
+    fn inverse(&self, target: Number, x: Number) -> Inverse { some(target.checked_sub(x)) }
+}
+
+impl Operator for Mul {
+    fn symbol(&self) -> &'static str { "*" }
+    fn apply(&self, acc: Number, x: Number) -> Option<Number> { acc.checked_mul(x) }
+    fn inverse(&self, target: Number, x: Number) -> Inverse {
+        match (x, target) {
+            (0, 0) => Inverse::Any,
+            (0, _) => Inverse::None,
+            _ => some(target.checked_div(x).filter(|_| target.checked_rem(x) == Some(0)))
+        }
+    }
+}
+
+fn l10(n: Number) -> Option<Number> {
+    10i128.checked_pow(n.checked_ilog10().unwrap_or(0) + 1)
+}
+
+impl Operator for Concat {
+    fn symbol(&self) -> &'static str { "||" }
+    fn apply(&self, acc: Number, x: Number) -> Option<Number> {
+        if acc < 0 || x < 0 { return None; }
+        acc.checked_mul(l10(x)?)?.checked_add(x)
+    }
+    fn inverse(&self, target: Number, x: Number) -> Inverse {
+        let Some(l) = l10(x).filter(|_| target >= 0 && x >= 0) else { return Inverse::None };
+        some(Some(target / l).filter(|_| target % l == x))
+    }
+}
+
+impl Operator for Sub {
+    fn symbol(&self) -> &'static str { "-" }
+    fn apply(&self, acc: Number, x: Number) -> Option<Number> { acc.checked_sub(x) }
+    fn inverse(&self, target: Number, x: Number) -> Inverse { some(target.checked_add(x)) }
+}
+
+impl Operator for Div {
+    fn symbol(&self) -> &'static str { "/" }
+    fn apply(&self, acc: Number, x: Number) -> Option<Number> {
+        acc.checked_div(x).filter(|_| acc.checked_rem(x) == Some(0))
+    }
+    fn inverse(&self, target: Number, x: Number) -> Inverse {
+        if x == 0 { Inverse::None } else { some(target.checked_mul(x)) }
+    }
+}
+
+impl Operator for Pow {
+    fn symbol(&self) -> &'static str { "^" }
+    fn apply(&self, acc: Number, x: Number) -> Option<Number> {
+        if acc < 0 { return None; }
+        acc.checked_pow(u32::try_from(x).ok()?)
+    }
+    fn inverse(&self, target: Number, x: Number) -> Inverse {
+        let Ok(n) = u32::try_from(x) else { return Inverse::None };
+        match (n, target) {
+            (0, 1) => Inverse::Any,
+            (0, _) => Inverse::None,
+            _ if target < 0 => Inverse::None,
+            _ => {
+                let root = target.nth_root(n);
+                some(Some(root).filter(|r| r.checked_pow(n) == Some(target)))
+            }
+        }
+    }
+}
+
+pub struct Solver {
+    ops: Vec<Box<dyn Operator>>
+}
+
+impl Solver {
+    pub fn new(ops: Vec<Box<dyn Operator>>) -> Self {
+        Solver { ops }
+    }
+
+    pub fn evaluate(&self, xs: &[Number], seq: &[usize]) -> Option<Number> {
+        let (first, rest) = xs.split_first()?;
+        if rest.len() != seq.len() { return None; }
+        rest.iter().zip(seq).try_fold(*first, |acc, (&x, &op)| self.ops[op].apply(acc, x))
+    }
+
+    pub fn describe(&self, xs: &[Number], seq: &[usize]) -> String {
+        let ops = seq.iter().map(|&op| self.ops[op].symbol());
+        xs.iter().map(|x| x.to_string()).interleave(ops.map(str::to_string)).join(" ")
+    }
+
+    pub fn solvable(&self, target: Number, xs: &[Number]) -> bool {
+        self.search(target, xs, &mut Vec::new(), &mut |_| ControlFlow::Break(())).is_break()
+    }
+
+    pub fn count(&self, target: Number, xs: &[Number]) -> usize {
+        let mut n = 0;
+        let _ = self.search(target, xs, &mut Vec::new(), &mut |_| { n += 1; ControlFlow::Continue(()) });
+        n
+    }
+
+    pub fn solutions(&self, target: Number, xs: &[Number]) -> Vec<Vec<usize>> {
+        let mut found = Vec::new();
+        let _ = self.search(target, xs, &mut Vec::new(), &mut |seq| { found.push(seq); ControlFlow::Continue(()) });
+        found
+    }
+
+    // `suffix` holds the operators chosen so far, last one first.
+    fn search(&self, target: Number, xs: &[Number], suffix: &mut Vec<usize>, found: &mut dyn FnMut(Vec<usize>) -> ControlFlow<()>) -> ControlFlow<()> {
+        match xs {
+            [] => ControlFlow::Continue(()),
+            [x] if *x == target => found(suffix.iter().rev().copied().collect()),
+            [_] => ControlFlow::Continue(()),
+            [rest @ .., x] => {
+                for (i, op) in self.ops.iter().enumerate() {
+                    suffix.push(i);
+                    match op.inverse(target, *x) {
+                        Inverse::None => ControlFlow::Continue(()),
+                        Inverse::One(t) => self.search(t, rest, suffix, found),
+                        Inverse::Any => self.forward(rest[0], &rest[1..], &mut Vec::new(), &mut |acc, mut seq| {
+                            if op.apply(acc, *x).is_none() { return ControlFlow::Continue(()); }
+                            seq.extend(suffix.iter().rev());
+                            found(seq)
+                        })
+                    }?;
+                    suffix.pop();
+                }
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    fn forward(&self, acc: Number, xs: &[Number], seq: &mut Vec<usize>, found: &mut dyn FnMut(Number, Vec<usize>) -> ControlFlow<()>) -> ControlFlow<()> {
+        let Some((&x, rest)) = xs.split_first() else { return found(acc, seq.clone()) };
+        for (i, op) in self.ops.iter().enumerate() {
+            if let Some(v) = op.apply(acc, x) {
+                seq.push(i);
+                self.forward(v, rest, seq, found)?;
+                seq.pop();
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_sequences(s: &Solver, target: Number, xs: &[Number]) -> usize {
+        (0..xs.len() - 1).map(|_| 0..s.ops.len())
+            .multi_cartesian_product()
+            .filter(|seq| s.evaluate(xs, seq) == Some(target))
+            .count()
+    }
+
+    #[test]
+    fn finds_operator_sequences() {
+        let s = Solver::new(vec![Box::new(Add), Box::new(Mul), Box::new(Concat)]);
+        let found = s.solutions(3267, &[81, 40, 27]);
+        assert_eq!(found.iter().map(|seq| s.describe(&[81, 40, 27], seq)).collect_vec(), ["81 * 40 + 27", "81 + 40 * 27"]);
+        assert_eq!(s.solutions(156, &[15, 6]), [vec![2]]);
+        assert!(s.solvable(7290, &[6, 8, 6, 15]));
+        assert!(!s.solvable(83, &[17, 5]));
+        assert_eq!(s.count(0, &[5, 3, 0]), 3);
+        assert_eq!(s.count(100, &[1, 0, 0]), 1);
+
+        let all = Solver::new(vec![Box::new(Add), Box::new(Mul), Box::new(Sub), Box::new(Div), Box::new(Pow), Box::new(Concat)]);
+        assert_eq!(all.solutions(-7, &[1, 8]), [vec![2]]);
+        assert_eq!(all.solutions(4, &[12, 3]), [vec![3]]);
+        assert_eq!(all.evaluate(&[Number::MIN, -1], &[3]), None);
+        assert_eq!(all.count(Number::MIN, &[5, -1]), 0);
+        for (target, xs) in [(16, vec![2, 2, 2]), (1, vec![5, 3, 0]), (0, vec![4, 0, 2, 1]), (10, vec![1, 2, 3, 4]), (-3, vec![3, 0, 2, 3]), (1, vec![2, 5, 0])] {
+            assert_eq!(all.count(target, &xs), all_sequences(&all, target, &xs), "{target}: {xs:?}");
+            assert!(all.solutions(target, &xs).iter().all(|seq| all.evaluate(&xs, seq) == Some(target)));
+        }
+    }
+}
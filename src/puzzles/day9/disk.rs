@@ -0,0 +1,220 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub id: usize,
+    pub start: usize,
+    pub len: usize
+}
+
+impl Span {
+    pub fn checksum(&self) -> usize {
+        self.id * (self.len * self.start + self.len * self.len.saturating_sub(1) / 2)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub id: usize,
+    pub from: usize,
+    pub to: usize,
+    pub len: usize
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Blocks,
+    Files
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiskError {
+    InvalidDigit { pos: usize, found: char }
+}
+
+// Starts of free spans, one min-heap per span length.
+pub struct FreeIndex {
+    heaps: Vec<BinaryHeap<Reverse<usize>>>
+}
+
+impl FreeIndex {
+    pub fn new(max_len: usize) -> Self {
+        FreeIndex { heaps: vec![BinaryHeap::new(); max_len + 1] }
+    }
+
+    pub fn insert(&mut self, start: usize, len: usize) {
+        if len > 0 { self.heaps[len].push(Reverse(start)); }
+    }
+
+    pub fn take(&mut self, len: usize, before: usize) -> Option<(usize, usize)> {
+        let size = (len..self.heaps.len())
+            .filter_map(|s| self.heaps[s].peek().map(|&Reverse(start)| (start, s)))
+            .filter(|&(start, _)| start < before)
+            .min()?
+            .1;
+        self.heaps[size].pop().map(|Reverse(start)| (start, size))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Compacted {
+    pub extents: Vec<Span>,
+    pub moves: Vec<Move>
+}
+
+impl Compacted {
+    pub fn checksum(&self) -> usize {
+        self.extents.iter().map(Span::checksum).sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskMap {
+    pub files: Vec<Span>,
+    pub free: Vec<(usize, usize)>,
+    pub len: usize
+}
+
+impl DiskMap {
+    pub fn parse(data: &str) -> Result<Self, DiskError> {
+        let mut files = Vec::new();
+        let mut free = Vec::new();
+        let mut start = 0;
+        for (pos, c) in data.trim_end().chars().enumerate() {
+            let len = c.to_digit(10).ok_or(DiskError::InvalidDigit { pos, found: c })? as usize;
+            if pos % 2 == 0 {
+                files.push(Span { id: pos / 2, start, len });
+            } else if let Some(last) = free.last_mut().filter(|(s, l)| s + l == start) {
+                // an empty file in between doesn't split the free space
+                last.1 += len;
+            } else {
+                free.push((start, len));
+            }
+            start += len;
+        }
+        Ok(DiskMap { files, free, len: start })
+    }
+
+    pub fn compact(&self, strategy: Strategy) -> Compacted {
+        match strategy {
+            Strategy::Blocks => self.compact_blocks(),
+            Strategy::Files => self.compact_files()
+        }
+    }
+
+    fn compact_blocks(&self) -> Compacted {
+        let mut extents = Vec::new();
+        let mut moves = Vec::new();
+        let mut files = self.files.clone();
+        let mut free = self.free.iter().copied().filter(|&(_, len)| len > 0);
+        let mut gap = free.next();
+        while let Some(mut f) = files.pop() {
+            while f.len > 0 {
+                let Some((start, len)) = gap.filter(|&(start, _)| start < f.start) else { break };
+                let n = len.min(f.len);
+                f.len -= n;
+                moves.push(Move { id: f.id, from: f.start + f.len, to: start, len: n });
+                extents.push(Span { id: f.id, start, len: n });
+                gap = if n == len { free.next() } else { Some((start + n, len - n)) };
+            }
+            if f.len > 0 {
+                // no gap left of this file, so neither is there one left of any file before it
+                extents.push(f);
+                extents.append(&mut files);
+            }
+        }
+        Compacted { extents, moves }
+    }
+
+    fn compact_files(&self) -> Compacted {
+        let mut index = FreeIndex::new(self.free.iter().map(|&(_, len)| len).max().unwrap_or(0));
+        self.free.iter().for_each(|&(start, len)| index.insert(start, len));
+        let mut moves = Vec::new();
+        let extents = self.files.iter().rev().map(|&f| {
+            if f.len == 0 { return f; }
+            let Some((start, size)) = index.take(f.len, f.start) else { return f };
+            index.insert(start + f.len, size - f.len);
+            moves.push(Move { id: f.id, from: f.start, to: start, len: f.len });
+            Span { start, ..f }
+        })
+        .collect();
+        Compacted { extents, moves }
+    }
+
+    pub fn render(len: usize, extents: &[Span]) -> String {
+        let mut blocks = vec!['.'; len];
+        for e in extents {
+            let c = char::from_digit((e.id % 10) as u32, 10).unwrap();
+            blocks[e.start..e.start + e.len].iter_mut().for_each(|b| *b = c);
+        }
+        blocks.into_iter().collect()
+    }
+
+    pub fn frames(&self, moves: &[Move]) -> Vec<String> {
+        let mut blocks = DiskMap::render(self.len, &self.files).into_bytes();
+        let mut frames = vec![String::from_utf8(blocks.clone()).unwrap()];
+        for m in moves {
+            let c = blocks[m.from];
+            blocks[m.from..m.from + m.len].iter_mut().for_each(|b| *b = b'.');
+            blocks[m.to..m.to + m.len].iter_mut().for_each(|b| *b = c);
+            frames.push(String::from_utf8(blocks.clone()).unwrap());
+        }
+        frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn naive(data: &str, strategy: Strategy) -> usize {
+        let map = DiskMap::parse(data).unwrap();
+        let mut blocks = vec![None; map.len];
+        map.files.iter().for_each(|f| (f.start..f.start + f.len).for_each(|b| blocks[b] = Some(f.id)));
+        match strategy {
+            Strategy::Blocks => {
+                let (mut l, mut r) = (0, blocks.len());
+                while l < r {
+                    if blocks[l].is_some() { l += 1; } else if blocks[r - 1].is_none() { r -= 1; } else { blocks.swap(l, r - 1); }
+                }
+            }
+            Strategy::Files => for f in map.files.iter().rev().filter(|f| f.len > 0) {
+                if let Some(to) = (0..f.start).find(|&s| blocks[s..s + f.len].iter().all(Option::is_none)) {
+                    (0..f.len).for_each(|k| blocks.swap(to + k, f.start + k));
+                }
+            }
+        }
+        blocks.iter().enumerate().map(|(i, b)| i * b.unwrap_or(0)).sum()
+    }
+
+    #[test]
+    fn compacts_like_block_by_block() {
+        let sample = "2333133121414131402";
+        let map = DiskMap::parse(sample).unwrap();
+        let files = map.compact(Strategy::Files);
+        assert_eq!(DiskMap::render(map.len, &files.extents), "00992111777.44.333....5555.6666.....8888..");
+        let blocks = map.compact(Strategy::Blocks);
+        assert_eq!(DiskMap::render(map.len, &blocks.extents), "0099811188827773336446555566..............");
+        let frames = map.frames(&files.moves);
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[1], "0099.111...2...333.44.5555.6666.777.8888..");
+        assert_eq!(frames.last().unwrap(), &DiskMap::render(map.len, &files.extents));
+        assert!(matches!(DiskMap::parse("12a"), Err(DiskError::InvalidDigit { pos: 2, found: 'a' })));
+        let mut rng = StdRng::seed_from_u64(9);
+        for len in [1, 2, 7, 500] {
+            let data = (0..len).map(|_| char::from_digit(rng.gen_range(0..10), 10).unwrap()).collect::<String>();
+            for s in [Strategy::Blocks, Strategy::Files] {
+                assert_eq!(DiskMap::parse(&data).unwrap().compact(s).checksum(), naive(&data, s), "{data} {s:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn handles_large_maps() {
+        let data = "9".repeat(2_000_000);
+        let map = DiskMap::parse(&data).unwrap();
+        assert_eq!(map.compact(Strategy::Blocks).extents.iter().map(|e| e.len).sum::<usize>(), 9 * 1_000_000);
+        assert_eq!(map.compact(Strategy::Files).moves.len(), 500_000);
+    }
+}